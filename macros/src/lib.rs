@@ -279,7 +279,8 @@ pub fn implement_func(item: proc_macro::TokenStream) -> proc_macro::TokenStream
     let parsed = parse_macro_input!(item as Test);
     let closure = parsed.impl_body.clone();
     let fmt_string = parsed.sl_impl_body.clone();
-    let glfunc = parsed.sl_func_name.clone().to_string().to_token_stream();
+    // raw identifiers allow builtins named like rust keywords, e.g. `r#mod`
+    let glfunc = parsed.sl_func_name.clone().to_string().trim_start_matches("r#").to_token_stream();
     let ret_type = parsed.impl_body.output;
     let ret_type = match ret_type {
         ReturnType::Type(_, x) => x,
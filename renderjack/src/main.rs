@@ -6,7 +6,9 @@ pub mod shader;
 pub mod transform;
 
 use motokigo::{
-	compiler, parser,
+	compiler,
+	diagnostic::Diagnostic,
+	parser,
 	vm::{self, *},
 };
 
@@ -272,7 +274,19 @@ fn main() {
 			std::fs::write("debug/shaders/basic/compiled.glsl", glsl.clone()).ok();
 			std::fs::write("res/shaders/glsl/basic.fs", glsl).unwrap();
 		}
-		let compiled = compiler::compile(program);
+		let compiled = match compiler::compile(program) {
+			Ok(compiled) => {
+				let warnings = compiled.data.warnings.iter().cloned().map(Diagnostic::from).collect::<Vec<_>>();
+				if !warnings.is_empty() {
+					eprintln!("{}", motokigo::diagnostic::render_all(warnings.iter(), &src));
+				}
+				compiled
+			}
+			Err(e) => {
+				eprintln!("{}", motokigo::diagnostic::render_all(e.diagnostics().iter(), &src));
+				std::process::exit(1);
+			}
+		};
 		std::fs::write("debug/shaders/basic/code.ron", format!("{:#?}", compiled)).ok();
 		compiled
 	};
@@ -383,6 +397,10 @@ fn main() {
 							result = s.resume();
 						}
 						VMState::VMRunFinished(s) => break s.0,
+						VMState::Error(s) => {
+							eprintln!("shader failed at pixel ({}, {}): {}", x, y, s.1);
+							std::process::exit(1);
+						}
 					};
 				};
 
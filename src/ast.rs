@@ -29,6 +29,17 @@ pub trait Visitor {
 	fn post_statement(&mut self, _t: &mut Statement) -> VResult {
 		Ok(())
 	}
+	/// Called before the statements of a nested block, i.e. the body of a conditional or loop.
+	fn pre_block(&mut self) -> VResult {
+		Ok(())
+	}
+	/// Called after the range of a `for` loop and before its body.
+	fn pre_loop_body(&mut self, _t: &mut ForLoop) -> VResult {
+		Ok(())
+	}
+	fn post_block(&mut self) -> VResult {
+		Ok(())
+	}
 	fn post_func_call(&mut self, _t: &mut FuncCall) -> VResult {
 		Ok(())
 	}
@@ -47,12 +58,64 @@ impl<T: Visitable> Visitable for Vec<T> {
 	}
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+fn visit_block(statements: &mut Vec<Statement>, v: &mut dyn Visitor) -> VResult {
+	v.pre_block()?;
+	statements.visit(v)?;
+	v.post_block()
+}
+
+/// Identifies the source file a position belongs to, for programs that are assembled from more than one input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct SourceId(pub u32);
+
+/// A location in the source code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub struct Position {
+	pub source: SourceId,
+	/// Byte offset from the start of the source.
+	pub offset: usize,
+	/// Line number, starting at 1.
 	pub line: u32,
-	pub offset: Option<u32>,
+	/// Column in characters (not bytes), starting at 1.
+	pub column: u32,
+}
+
+impl Position {
+	/// The position of the first character in `source`.
+	pub fn start(source: SourceId) -> Position {
+		Position {
+			source,
+			offset: 0,
+			line: 1,
+			column: 1,
+		}
+	}
+
+	/// The position directly after `c`, if `c` starts at this position.
+	pub fn advance(self, c: char) -> Position {
+		match c {
+			'\n' => Position {
+				offset: self.offset + 1,
+				line: self.line + 1,
+				column: 1,
+				..self
+			},
+			c => Position {
+				offset: self.offset + c.len_utf8(),
+				column: self.column + 1,
+				..self
+			},
+		}
+	}
+}
+
+impl fmt::Display for Position {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}:{}", self.line, self.column)
+	}
 }
 
+/// An item together with the source range it was parsed from. `to` is exclusive.
 #[derive(Clone)]
 pub struct Spanned<T> {
 	pub item: T,
@@ -66,12 +129,8 @@ where
 {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
 		f.write_fmt(format_args!(
-			"Span [{}:{}, {}:{}], Item: {:?}",
-			self.from.line,
-			self.from.offset.unwrap_or_default(),
-			self.to.line,
-			self.to.offset.unwrap_or_default(),
-			self.item
+			"Span [{}, {}], Item: {:?}",
+			self.from, self.to, self.item
 		))
 	}
 }
@@ -86,8 +145,8 @@ impl<T> Spanned<T> {
 	pub fn empty() -> Spanned<()> {
 		Spanned {
 			item: (),
-			from: Position { line: 0, offset: None },
-			to: Position { line: 0, offset: None },
+			from: Position::default(),
+			to: Position::default(),
 		}
 	}
 
@@ -113,9 +172,23 @@ impl<T> Spanned<T> {
 	pub fn just_span(&self) -> Spanned<()> {
 		self.map(|_| ())
 	}
+
+	pub fn source(&self) -> SourceId {
+		self.from.source
+	}
+
+	/// The byte range this span covers in its source.
+	pub fn byte_range(&self) -> Range<usize> {
+		self.from.offset..self.to.offset
+	}
+
+	/// The text this span covers, if `source` is the text it was scanned from.
+	pub fn slice<'a>(&self, source: &'a str) -> Option<&'a str> {
+		source.get(self.byte_range())
+	}
 }
 
-use std::ops::{Deref, DerefMut};
+use std::ops::{Deref, DerefMut, Range};
 
 impl<T> Deref for Spanned<T> {
 	type Target = T;
@@ -156,13 +229,17 @@ impl<T: fmt::Debug, R: fmt::Debug> fmt::Debug for Reference<T, R> {
 #[derive(Clone, Debug, Copy)]
 pub enum Literal {
 	IntegerLiteral(i64),
+	UnsignedIntegerLiteral(u64),
 	DecimalLiteral(f64),
+	BoolLiteral(bool),
 }
 
 impl Literal {
 	pub fn to_string(&self) -> String {
 		match self {
 			Literal::IntegerLiteral(i) => format!("{}", i),
+			Literal::UnsignedIntegerLiteral(i) => format!("{}u", i),
+			Literal::BoolLiteral(b) => format!("{}", b),
 			Literal::DecimalLiteral(f) => {
 				if f.fract() == 0.0 {
 					format!("{}.0", f)
@@ -176,13 +253,21 @@ impl Literal {
 
 #[derive(Debug, Clone)]
 pub enum TypeKind {
+	/// The type of an expression that failed to type check.
+	Error,
 	Void,
+	Bool,
 	I32,
+	U32,
 	F32,
 	TypeRef(Spanned<Ident>),
 	Vector(Box<TypeKind>, usize),
 	Matrix(Box<TypeKind>, usize, usize),
+	/// A fixed-size array like `Float[8]`.
+	Array(Box<TypeKind>, usize),
 	Struct(Rc<RefCell<StructDeclaration>>),
+	/// An opaque handle to a texture, only usable as a uniform or a function parameter.
+	Sampler2D,
 }
 
 impl std::cmp::PartialEq for TypeKind {
@@ -190,26 +275,65 @@ impl std::cmp::PartialEq for TypeKind {
 		use TypeKind::*;
 
 		match (self, other) {
+			(Void, Void) => true,
+			(Bool, Bool) => true,
 			(I32, I32) => true,
+			(U32, U32) => true,
 			(F32, F32) => true,
 			(TypeRef(a), TypeRef(b)) => &a.item == &b.item,
 			(Vector(ta, na), Vector(tb, nb)) => ta == tb && na == nb,
 			(Matrix(ta, na, ma), Matrix(tb, nb, mb)) => ta == tb && na == nb && ma == mb,
+			(Array(ta, na), Array(tb, nb)) => ta == tb && na == nb,
 			(Struct(a), Struct(b)) => &a.borrow().ident.item == &b.borrow().ident.item,
+			(Sampler2D, Sampler2D) => true,
 			_ => false,
 		}
 	}
 }
 
+impl fmt::Display for TypeKind {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			TypeKind::Error => write!(f, "{{error}}"),
+			TypeKind::Void => write!(f, "Void"),
+			TypeKind::Bool => write!(f, "Bool"),
+			TypeKind::I32 => write!(f, "Int"),
+			TypeKind::U32 => write!(f, "UInt"),
+			TypeKind::F32 => write!(f, "Float"),
+			TypeKind::TypeRef(name) => write!(f, "{}", name.item),
+			TypeKind::Vector(element, n) => match **element {
+				TypeKind::I32 => write!(f, "IVec{}", n),
+				TypeKind::U32 => write!(f, "UVec{}", n),
+				TypeKind::Bool => write!(f, "BVec{}", n),
+				_ => write!(f, "Vec{}", n),
+			},
+			TypeKind::Matrix(_, m, n) if m == n => write!(f, "Mat{}", m),
+			TypeKind::Matrix(_, m, n) => write!(f, "Mat{}x{}", m, n),
+			TypeKind::Array(element, n) => write!(f, "{}[{}]", element, n),
+			TypeKind::Struct(s) => write!(f, "{}", s.borrow().ident.item),
+			TypeKind::Sampler2D => write!(f, "Sampler2D"),
+		}
+	}
+}
+
 impl TypeKind {
+	pub fn is_error(&self) -> bool {
+		matches!(self, TypeKind::Error)
+	}
+
 	pub fn size(&self) -> usize {
 		match self {
+			TypeKind::Error => 0,
 			TypeKind::Void => 0,
+			TypeKind::Bool => 4,
 			TypeKind::I32 => 4,
+			TypeKind::U32 => 4,
 			TypeKind::F32 => 4,
 			TypeKind::Vector(type_kind, size) => type_kind.size() * size,
 			TypeKind::Matrix(type_kind, m, n) => type_kind.size() * m * n,
+			TypeKind::Array(type_kind, n) => type_kind.size() * n,
 			TypeKind::Struct(s) => s.borrow().size.unwrap(),
+			TypeKind::Sampler2D => 4,
 			_ => unimplemented!("{:?}", self),
 		}
 	}
@@ -245,13 +369,22 @@ pub enum Expr {
 	FuncCall(FuncCall),
 	Literal(Spanned<Literal>),
 	Symbol(Symbol),
-	FieldAccess(Symbol, Spanned<Ident>, Option<TypeKind>, Option<usize>),
+	/// `expr.field`, either a struct member or a vector swizzle. For struct members, the last element is the byte
+	/// offset of the member inside the struct.
+	FieldAccess(Box<Expr>, Spanned<Ident>, Option<TypeKind>, Option<usize>),
+	/// `expr[index]`, the span covers the brackets.
+	Index(Box<Expr>, Box<Expr>, Spanned<()>, Option<TypeKind>),
+	/// `[a, b, c]`, the span covers the brackets.
+	ArrayLiteral(Spanned<()>, Vec<Box<Expr>>),
 	StructConstruction(
 		Spanned<Ident>,
 		Option<Rc<RefCell<StructDeclaration>>>,
 		Vec<(Spanned<Ident>, Box<Expr>)>,
 	),
 	Grouped(Box<Expr>),
+	/// An implicit conversion of the expression to the type, inserted during type resolution when an argument
+	/// doesn't match the parameter of the function it is passed to exactly.
+	Conversion(Box<Expr>, TypeKind),
 }
 
 impl Expr {
@@ -260,12 +393,20 @@ impl Expr {
 			Expr::FuncCall((def, _)) => def.resolved.clone().map(|(_, tk)| tk),
 			Expr::Symbol(s) => s.resolved.clone().map(|(_, tk)| tk),
 			Expr::FieldAccess(_, _, tk, _) => tk.clone(),
+			Expr::Index(_, _, _, tk) => tk.clone(),
+			Expr::ArrayLiteral(_, elements) => elements
+				.first()
+				.and_then(|e| e.typekind())
+				.map(|tk| TypeKind::Array(Box::new(tk), elements.len())),
 			Expr::StructConstruction(_, s, _) => s.clone().map(|s| TypeKind::Struct(s)),
 			Expr::Literal(l) => match l.item {
 				Literal::DecimalLiteral(_) => Some(TypeKind::F32),
 				Literal::IntegerLiteral(_) => Some(TypeKind::I32),
+				Literal::UnsignedIntegerLiteral(_) => Some(TypeKind::U32),
+				Literal::BoolLiteral(_) => Some(TypeKind::Bool),
 			},
 			Expr::Grouped(e) => e.typekind(),
+			Expr::Conversion(_, tk) => Some(tk.clone()),
 		}
 	}
 
@@ -274,14 +415,27 @@ impl Expr {
 			.expect(&format!("Expected expr {:#?} to be typed by this point.", self))
 	}
 
+	/// The variable a place expression like `a.b.xy` refers to, or `None` if the expression cannot be assigned to.
+	pub fn place_root(&self) -> Option<&Symbol> {
+		match self {
+			Expr::Symbol(s) => Some(s),
+			Expr::FieldAccess(e, _, _, _) => e.place_root(),
+			Expr::Index(e, _, _, _) => e.place_root(),
+			_ => None,
+		}
+	}
+
 	pub fn span(&self) -> Spanned<()> {
 		match self {
 			Self::FuncCall(fc) => fc.0.raw.map(|_| ()),
 			Self::Literal(lit) => lit.map(|_| ()),
 			Self::Symbol(sym) => sym.raw.map(|_| ()),
-			Self::FieldAccess(sym, i, _, _) => Spanned::encompass((), sym.raw.just_span(), i.just_span()),
+			Self::FieldAccess(e, i, _, _) => Spanned::encompass((), e.span(), i.just_span()),
+			Self::Index(e, _, brackets, _) => Spanned::encompass((), e.span(), *brackets),
+			Self::ArrayLiteral(brackets, _) => *brackets,
 			Self::StructConstruction(name, _, _) => name.just_span(),
 			Self::Grouped(e) => e.span(),
+			Self::Conversion(e, _) => e.span(),
 		}
 	}
 }
@@ -296,8 +450,15 @@ impl Visitable for Expr {
 				v.post_func_call(func)?;
 			}
 			Expr::Symbol(s) => s.visit(v)?,
-			Expr::FieldAccess(s, _, _, _) => {
-				s.visit(v)?;
+			Expr::FieldAccess(e, _, _, _) => e.visit(v)?,
+			Expr::Index(e, index, _, _) => {
+				e.visit(v)?;
+				index.visit(v)?;
+			}
+			Expr::ArrayLiteral(_, elements) => {
+				for e in elements {
+					e.visit(v)?;
+				}
 			}
 			Expr::StructConstruction(_, _, fields) => {
 				for (_, e) in fields {
@@ -306,6 +467,7 @@ impl Visitable for Expr {
 			}
 			Expr::Literal(_) => (),
 			Expr::Grouped(e) => e.visit(v)?,
+			Expr::Conversion(e, _) => e.visit(v)?,
 		}
 
 		v.post_expr(self)
@@ -314,11 +476,33 @@ impl Visitable for Expr {
 
 #[derive(Clone, Debug)]
 pub enum Statement {
-	Assignment(Spanned<Ident>, Expr),
+	/// Assigns the second expression to the first, which is a variable or a field/swizzle of one.
+	Assignment(Expr, Expr),
 	VariableDeclaration(bool, Spanned<Ident>, Expr),
-	Return(Spanned<()>, Expr),
+	/// Returns from the function, with a value unless it returns `Void`.
+	Return(Spanned<()>, Option<Expr>),
+	/// Evaluates an expression and discards its value, e.g. a call to a `Void` function.
+	Expression(Expr),
 	Conditional(Conditional),
-	Loop(Spanned<Ident>, Expr, Expr, Vec<Statement>),
+	Loop(Box<ForLoop>),
+	While(Expr, Vec<Statement>),
+	Break(Spanned<()>),
+	Continue(Spanned<()>),
+}
+
+impl Statement {
+	/// The span of the beginning of the statement, e.g. the variable it declares or the condition of an `if`.
+	pub fn span(&self) -> Spanned<()> {
+		match self {
+			Statement::Assignment(place, _) => place.span(),
+			Statement::VariableDeclaration(_, ident, _) => ident.just_span(),
+			Statement::Return(span, _) | Statement::Break(span) | Statement::Continue(span) => *span,
+			Statement::Conditional(c) => c.cond.as_ref().map_or_else(Spanned::<()>::empty, Expr::span),
+			Statement::Loop(l) => l.ident.just_span(),
+			Statement::While(cond, _) => cond.span(),
+			Statement::Expression(expr) => expr.span(),
+		}
+	}
 }
 
 impl Visitable for Statement {
@@ -326,21 +510,51 @@ impl Visitable for Statement {
 		v.pre_statement(self)?;
 
 		match self {
-			Statement::Assignment(_, expr) => expr.visit(v)?,
+			Statement::Assignment(place, expr) => {
+				place.visit(v)?;
+				expr.visit(v)?;
+			}
 			Statement::VariableDeclaration(_, _, expr) => expr.visit(v)?,
-			Statement::Return(_, expr) => expr.visit(v)?,
+			Statement::Return(_, Some(expr)) => expr.visit(v)?,
+			Statement::Return(_, None) => {}
+			Statement::Expression(expr) => expr.visit(v)?,
 			Statement::Conditional(cond) => cond.visit(v)?,
-			Statement::Loop(_, from, to, body) => {
-				from.visit(v)?;
-				to.visit(v)?;
-				body.visit(v)?;
+			Statement::Loop(l) => l.visit(v)?,
+			Statement::While(cond, body) => {
+				cond.visit(v)?;
+				visit_block(body, v)?;
 			}
+			Statement::Break(_) | Statement::Continue(_) => {}
 		}
 
 		v.post_statement(self)
 	}
 }
 
+/// A loop like `for i = from to to step step { body }`, which counts from `from` towards `to`, excluding `to`.
+#[derive(Clone, Debug)]
+pub struct ForLoop {
+	pub ident: Spanned<Ident>,
+	pub from: Expr,
+	pub to: Expr,
+	/// A constant added to the counter after every iteration, `1` if omitted. Its sign decides whether the loop counts
+	/// up or down.
+	pub step: Option<Expr>,
+	pub body: Vec<Statement>,
+}
+
+impl Visitable for ForLoop {
+	fn visit(&mut self, v: &mut dyn Visitor) -> VResult {
+		self.from.visit(v)?;
+		self.to.visit(v)?;
+		if let Some(step) = &mut self.step {
+			step.visit(v)?;
+		}
+		v.pre_loop_body(self)?;
+		visit_block(&mut self.body, v)
+	}
+}
+
 #[derive(Clone, Debug)]
 pub struct Conditional {
 	pub cond: Option<Expr>,
@@ -353,7 +567,7 @@ impl Visitable for Conditional {
 		if let Some(cond) = &mut self.cond {
 			cond.visit(v)?;
 		}
-		self.body.visit(v)?;
+		visit_block(&mut self.body, v)?;
 		if let Some(alt) = self.alternate.as_mut() {
 			alt.visit(v)?;
 		}
@@ -367,6 +581,8 @@ pub struct FunctionDeclaration {
 	pub params: Vec<(Spanned<TypeKind>, Spanned<Ident>)>,
 	pub statements: Vec<Statement>,
 	pub ret_type: Spanned<TypeKind>,
+	/// The `///` comments preceding the declaration, one line each.
+	pub doc: Option<String>,
 }
 
 impl Visitable for FunctionDeclaration {
@@ -385,6 +601,7 @@ pub struct InParameterDeclaration {
 	pub type_kind: Spanned<TypeKind>,
 	pub ident: Spanned<Ident>,
 	pub is_uniform: bool,
+	pub doc: Option<String>,
 }
 
 impl Visitable for InParameterDeclaration {
@@ -399,6 +616,7 @@ pub struct StructDeclaration {
 	pub ident: Spanned<Ident>,
 	pub members: Vec<(Spanned<Ident>, Spanned<TypeKind>)>,
 	pub size: Option<usize>,
+	pub doc: Option<String>,
 }
 
 impl Visitable for StructDeclaration {
@@ -9,12 +9,14 @@ macro_rules! implement_vec_op {
             implement_func!(BinMul, __op_binary_mul, |a: $name, b: $comp| -> $name { a * b }, "{} * {}");
             implement_func!(BinMul, __op_binary_mul, |a: $comp, b: $name| -> $name { b * a }, "{} * {}");
             implement_func!(BinDiv, __op_binary_div, |a: $name, b: $comp| -> $name { a / b }, "{} / {}");
+            implement_func!(BinMul, __op_binary_mul, |a: $name, b: $name| -> $name { a.zip_with(b, |x, y| x * y) }, "{} * {}");
+            implement_func!(BinDiv, __op_binary_div, |a: $name, b: $name| -> $name { a.zip_with(b, |x, y| x / y) }, "{} / {}");
             implement_func!(BinAdd, __op_binary_add, |a: $name, b: $name| -> $name { a + b }, "{} + {}");
             implement_func!(BinSub, __op_binary_sub, |a: $name, b: $name| -> $name { a - b }, "{} - {}");
 
             implement_func!(BinNeg, __op_unary_neg, |a: $name| -> $name { a * (-1.0 as $comp) }, "-{}");
-            implement_func!(BinEquality, __op_binary_equality, |a: $name, b: $name| -> Int { if a == b { 1 } else { 0 } }, "{} == {}");
-            implement_func!(BinNotEqual, __op_binary_not_equal, |a: $name, b: $name| -> Int { if a != b { 1 } else { 0 } }, "{} != {}");
+            implement_func!(BinEquality, __op_binary_equality, |a: $name, b: $name| -> Bool { (a == b).into() }, "{} == {}");
+            implement_func!(BinNotEqual, __op_binary_not_equal, |a: $name, b: $name| -> Bool { (a != b).into() }, "{} != {}");
 		}
 	};
 }
@@ -37,17 +39,176 @@ generate_matrix_ctor!(4, 2);
 generate_matrix_ctor!(4, 3);
 generate_matrix_ctor!(4, 4);
 
+// like the scalar ops, division by zero results in zero
+macro_rules! implement_int_vec_op {
+	( $name:ident, $comp:ident ) => {
+		paste::item! {
+            implement_func!(BinMul, __op_binary_mul, |a: $name, b: $comp| -> $name { a.map(|x| x.wrapping_mul(b)) }, "{} * {}");
+            implement_func!(BinMul, __op_binary_mul, |a: $comp, b: $name| -> $name { b.map(|x| a.wrapping_mul(x)) }, "{} * {}");
+            implement_func!(BinDiv, __op_binary_div, |a: $name, b: $comp| -> $name { a.map(|x| if b == 0 { 0 } else { x.wrapping_div(b) }) }, "{} / {}");
+            implement_func!(BinMul, __op_binary_mul, |a: $name, b: $name| -> $name { a.zip_with(b, $comp::wrapping_mul) }, "{} * {}");
+            implement_func!(BinDiv, __op_binary_div, |a: $name, b: $name| -> $name { a.zip_with(b, |x, y| if y == 0 { 0 } else { x.wrapping_div(y) }) }, "{} / {}");
+            implement_func!(BinAdd, __op_binary_add, |a: $name, b: $name| -> $name { a.zip_with(b, $comp::wrapping_add) }, "{} + {}");
+            implement_func!(BinSub, __op_binary_sub, |a: $name, b: $name| -> $name { a.zip_with(b, $comp::wrapping_sub) }, "{} - {}");
+
+            implement_func!(BinEquality, __op_binary_equality, |a: $name, b: $name| -> Bool { (a == b).into() }, "{} == {}");
+            implement_func!(BinNotEqual, __op_binary_not_equal, |a: $name, b: $name| -> Bool { (a != b).into() }, "{} != {}");
+		}
+	};
+}
+
+implement_int_vec_op!(IVec4, Int);
+implement_int_vec_op!(IVec3, Int);
+implement_int_vec_op!(IVec2, Int);
+implement_int_vec_op!(UVec4, UInt);
+implement_int_vec_op!(UVec3, UInt);
+implement_int_vec_op!(UVec2, UInt);
+
+implement_func!(BinNeg, __op_unary_neg, |a: IVec4| -> IVec4 { a.map(Int::wrapping_neg) }, "-{}");
+implement_func!(BinNeg, __op_unary_neg, |a: IVec3| -> IVec3 { a.map(Int::wrapping_neg) }, "-{}");
+implement_func!(BinNeg, __op_unary_neg, |a: IVec2| -> IVec2 { a.map(Int::wrapping_neg) }, "-{}");
+
+macro_rules! implement_vec_comparisons {
+	( $name:ident, $bvec:ident ) => {
+		paste::item! {
+            implement_func!(LessThan, lessThan, |a: $name, b: $name| -> $bvec { a.zip_with(b, |x, y| Bool::from(x < y)) }, "lessThan({}, {})");
+            implement_func!(LessThanEqual, lessThanEqual, |a: $name, b: $name| -> $bvec { a.zip_with(b, |x, y| Bool::from(x <= y)) }, "lessThanEqual({}, {})");
+            implement_func!(GreaterThan, greaterThan, |a: $name, b: $name| -> $bvec { a.zip_with(b, |x, y| Bool::from(x > y)) }, "greaterThan({}, {})");
+            implement_func!(GreaterThanEqual, greaterThanEqual, |a: $name, b: $name| -> $bvec { a.zip_with(b, |x, y| Bool::from(x >= y)) }, "greaterThanEqual({}, {})");
+            implement_func!(Equal, equal, |a: $name, b: $name| -> $bvec { a.zip_with(b, |x, y| Bool::from(x == y)) }, "equal({}, {})");
+            implement_func!(NotEqual, notEqual, |a: $name, b: $name| -> $bvec { a.zip_with(b, |x, y| Bool::from(x != y)) }, "notEqual({}, {})");
+		}
+	};
+}
+
+implement_vec_comparisons!(Vec4, BVec4);
+implement_vec_comparisons!(Vec3, BVec3);
+implement_vec_comparisons!(Vec2, BVec2);
+implement_vec_comparisons!(IVec4, BVec4);
+implement_vec_comparisons!(IVec3, BVec3);
+implement_vec_comparisons!(IVec2, BVec2);
+implement_vec_comparisons!(UVec4, BVec4);
+implement_vec_comparisons!(UVec3, BVec3);
+implement_vec_comparisons!(UVec2, BVec2);
+
+macro_rules! implement_bvec_ops {
+	( $name:ident ) => {
+		paste::item! {
+            implement_func!(BinEquality, __op_binary_equality, |a: $name, b: $name| -> Bool { (a == b).into() }, "{} == {}");
+            implement_func!(BinNotEqual, __op_binary_not_equal, |a: $name, b: $name| -> Bool { (a != b).into() }, "{} != {}");
+            implement_func!(Equal, equal, |a: $name, b: $name| -> $name { a.zip_with(b, |x, y| Bool::from(x == y)) }, "equal({}, {})");
+            implement_func!(NotEqual, notEqual, |a: $name, b: $name| -> $name { a.zip_with(b, |x, y| Bool::from(x != y)) }, "notEqual({}, {})");
+            implement_func!(Any, any, |a: $name| -> Bool { a.to_arr().iter().any(|&x| x.into()).into() }, "any({})");
+            implement_func!(All, all, |a: $name| -> Bool { a.to_arr().iter().all(|&x| x.into()).into() }, "all({})");
+            implement_func!(Not, not, |a: $name| -> $name { a.map(|x| Bool::from(!bool::from(x))) }, "not({})");
+		}
+	};
+}
+
+implement_bvec_ops!(BVec4);
+implement_bvec_ops!(BVec3);
+implement_bvec_ops!(BVec2);
+
+implement_func!(IVec2Ctor, IVec2, |x: Int, y: Int| -> IVec2 { IVec2::new(x, y) }, "ivec2({}, {})");
+implement_func!(IVec3Ctor, IVec3, |x: Int, y: Int, z: Int| -> IVec3 { IVec3::new(x, y, z) }, "ivec3({}, {}, {})");
+implement_func!(IVec4Ctor, IVec4, |x: Int, y: Int, z: Int, w: Int| -> IVec4 { IVec4::new(x, y, z, w) }, "ivec4({}, {}, {}, {})");
+implement_func!(UVec2Ctor, UVec2, |x: UInt, y: UInt| -> UVec2 { UVec2::new(x, y) }, "uvec2({}, {})");
+implement_func!(UVec3Ctor, UVec3, |x: UInt, y: UInt, z: UInt| -> UVec3 { UVec3::new(x, y, z) }, "uvec3({}, {}, {})");
+implement_func!(UVec4Ctor, UVec4, |x: UInt, y: UInt, z: UInt, w: UInt| -> UVec4 { UVec4::new(x, y, z, w) }, "uvec4({}, {}, {}, {})");
+implement_func!(BVec2Ctor, BVec2, |x: Bool, y: Bool| -> BVec2 { BVec2::new(x, y) }, "bvec2({}, {})");
+implement_func!(BVec3Ctor, BVec3, |x: Bool, y: Bool, z: Bool| -> BVec3 { BVec3::new(x, y, z) }, "bvec3({}, {}, {})");
+implement_func!(BVec4Ctor, BVec4, |x: Bool, y: Bool, z: Bool, w: Bool| -> BVec4 { BVec4::new(x, y, z, w) }, "bvec4({}, {}, {}, {})");
+
+// conversions between vectors of the same size, e.g. `IVec3(Vec3(1.5, 2.0, 0.0))`
+macro_rules! implement_vec_conversions {
+	( $vec:ident, $ivec:ident, $uvec:ident, $bvec:ident, $glvec:literal, $glivec:literal, $gluvec:literal, $glbvec:literal ) => {
+		paste::item! {
+            implement_func!([<To $vec>], $vec, |a: $ivec| -> $vec { a.map(|x| x as Float) }, $glvec);
+            implement_func!([<To $vec>], $vec, |a: $uvec| -> $vec { a.map(|x| x as Float) }, $glvec);
+            implement_func!([<To $vec>], $vec, |a: $bvec| -> $vec { a.map(|x| x.0 as Float) }, $glvec);
+            implement_func!([<To $ivec>], $ivec, |a: $vec| -> $ivec { a.map(|x| x as Int) }, $glivec);
+            implement_func!([<To $ivec>], $ivec, |a: $uvec| -> $ivec { a.map(|x| x as Int) }, $glivec);
+            implement_func!([<To $ivec>], $ivec, |a: $bvec| -> $ivec { a.map(|x| x.0 as Int) }, $glivec);
+            implement_func!([<To $uvec>], $uvec, |a: $vec| -> $uvec { a.map(|x| x as UInt) }, $gluvec);
+            implement_func!([<To $uvec>], $uvec, |a: $ivec| -> $uvec { a.map(|x| x as UInt) }, $gluvec);
+            implement_func!([<To $uvec>], $uvec, |a: $bvec| -> $uvec { a.map(|x| x.0) }, $gluvec);
+            implement_func!([<To $bvec>], $bvec, |a: $vec| -> $bvec { a.map(|x| Bool::from(x != 0.0)) }, $glbvec);
+            implement_func!([<To $bvec>], $bvec, |a: $ivec| -> $bvec { a.map(|x| Bool::from(x != 0)) }, $glbvec);
+            implement_func!([<To $bvec>], $bvec, |a: $uvec| -> $bvec { a.map(|x| Bool::from(x != 0)) }, $glbvec);
+		}
+	};
+}
+
+implement_vec_conversions!(Vec2, IVec2, UVec2, BVec2, "vec2({})", "ivec2({})", "uvec2({})", "bvec2({})");
+implement_vec_conversions!(Vec3, IVec3, UVec3, BVec3, "vec3({})", "ivec3({})", "uvec3({})", "bvec3({})");
+implement_vec_conversions!(Vec4, IVec4, UVec4, BVec4, "vec4({})", "ivec4({})", "uvec4({})", "bvec4({})");
+
+// `MatMxN` has M columns and N rows, so it is multiplied with an M component vector from the right
+macro_rules! implement_mat_vec_op {
+	( $mat:ident, $cols:ident, $rows:ident ) => {
+		paste::item! {
+            implement_func!(BinMul, __op_binary_mul, |a: $mat, b: $cols| -> $rows { a.mul_vec(b) }, "{} * {}");
+            implement_func!(BinMul, __op_binary_mul, |a: $rows, b: $mat| -> $cols { b.vec_mul(a) }, "{} * {}");
+		}
+	};
+}
+
+implement_mat_vec_op!(Mat2, Vec2, Vec2);
+implement_mat_vec_op!(Mat2x3, Vec2, Vec3);
+implement_mat_vec_op!(Mat2x4, Vec2, Vec4);
+implement_mat_vec_op!(Mat3x2, Vec3, Vec2);
+implement_mat_vec_op!(Mat3, Vec3, Vec3);
+implement_mat_vec_op!(Mat3x4, Vec3, Vec4);
+implement_mat_vec_op!(Mat4x2, Vec4, Vec2);
+implement_mat_vec_op!(Mat4x3, Vec4, Vec3);
+implement_mat_vec_op!(Mat4, Vec4, Vec4);
+
+implement_func!(BinMul, __op_binary_mul, |a: Mat2, b: Mat2| -> Mat2 { a.mul_mat(b) }, "{} * {}");
+implement_func!(BinMul, __op_binary_mul, |a: Mat2, b: Mat3x2| -> Mat3x2 { a.mul_mat(b) }, "{} * {}");
+implement_func!(BinMul, __op_binary_mul, |a: Mat2, b: Mat4x2| -> Mat4x2 { a.mul_mat(b) }, "{} * {}");
+implement_func!(BinMul, __op_binary_mul, |a: Mat2x3, b: Mat2| -> Mat2x3 { a.mul_mat(b) }, "{} * {}");
+implement_func!(BinMul, __op_binary_mul, |a: Mat2x3, b: Mat3x2| -> Mat3 { a.mul_mat(b) }, "{} * {}");
+implement_func!(BinMul, __op_binary_mul, |a: Mat2x3, b: Mat4x2| -> Mat4x3 { a.mul_mat(b) }, "{} * {}");
+implement_func!(BinMul, __op_binary_mul, |a: Mat2x4, b: Mat2| -> Mat2x4 { a.mul_mat(b) }, "{} * {}");
+implement_func!(BinMul, __op_binary_mul, |a: Mat2x4, b: Mat3x2| -> Mat3x4 { a.mul_mat(b) }, "{} * {}");
+implement_func!(BinMul, __op_binary_mul, |a: Mat2x4, b: Mat4x2| -> Mat4 { a.mul_mat(b) }, "{} * {}");
+implement_func!(BinMul, __op_binary_mul, |a: Mat3x2, b: Mat2x3| -> Mat2 { a.mul_mat(b) }, "{} * {}");
+implement_func!(BinMul, __op_binary_mul, |a: Mat3x2, b: Mat3| -> Mat3x2 { a.mul_mat(b) }, "{} * {}");
+implement_func!(BinMul, __op_binary_mul, |a: Mat3x2, b: Mat4x3| -> Mat4x2 { a.mul_mat(b) }, "{} * {}");
+implement_func!(BinMul, __op_binary_mul, |a: Mat3, b: Mat2x3| -> Mat2x3 { a.mul_mat(b) }, "{} * {}");
+implement_func!(BinMul, __op_binary_mul, |a: Mat3, b: Mat3| -> Mat3 { a.mul_mat(b) }, "{} * {}");
+implement_func!(BinMul, __op_binary_mul, |a: Mat3, b: Mat4x3| -> Mat4x3 { a.mul_mat(b) }, "{} * {}");
+implement_func!(BinMul, __op_binary_mul, |a: Mat3x4, b: Mat2x3| -> Mat2x4 { a.mul_mat(b) }, "{} * {}");
+implement_func!(BinMul, __op_binary_mul, |a: Mat3x4, b: Mat3| -> Mat3x4 { a.mul_mat(b) }, "{} * {}");
+implement_func!(BinMul, __op_binary_mul, |a: Mat3x4, b: Mat4x3| -> Mat4 { a.mul_mat(b) }, "{} * {}");
+implement_func!(BinMul, __op_binary_mul, |a: Mat4x2, b: Mat2x4| -> Mat2 { a.mul_mat(b) }, "{} * {}");
+implement_func!(BinMul, __op_binary_mul, |a: Mat4x2, b: Mat3x4| -> Mat3x2 { a.mul_mat(b) }, "{} * {}");
+implement_func!(BinMul, __op_binary_mul, |a: Mat4x2, b: Mat4| -> Mat4x2 { a.mul_mat(b) }, "{} * {}");
+implement_func!(BinMul, __op_binary_mul, |a: Mat4x3, b: Mat2x4| -> Mat2x3 { a.mul_mat(b) }, "{} * {}");
+implement_func!(BinMul, __op_binary_mul, |a: Mat4x3, b: Mat3x4| -> Mat3 { a.mul_mat(b) }, "{} * {}");
+implement_func!(BinMul, __op_binary_mul, |a: Mat4x3, b: Mat4| -> Mat4x3 { a.mul_mat(b) }, "{} * {}");
+implement_func!(BinMul, __op_binary_mul, |a: Mat4, b: Mat2x4| -> Mat2x4 { a.mul_mat(b) }, "{} * {}");
+implement_func!(BinMul, __op_binary_mul, |a: Mat4, b: Mat3x4| -> Mat3x4 { a.mul_mat(b) }, "{} * {}");
+implement_func!(BinMul, __op_binary_mul, |a: Mat4, b: Mat4| -> Mat4 { a.mul_mat(b) }, "{} * {}");
+
 macro_rules! implement_common_num_ops {
 	( $name:ident ) => {
 		paste::item! {
-            implement_func!(UnNeg, __op_unary_neg, |a: $name| -> $name { -a }, "-{}");
-            implement_func!(BinEquality, __op_binary_equality, |a: $name, b: $name| -> Int { if a == b { 1 } else { 0 } }, "{} == {}");
-            implement_func!(BinNotEqual, __op_binary_not_equal, |a: $name, b: $name| -> Int { if a != b { 1 } else { 0 } }, "{} != {}");
+            implement_func!(BinEquality, __op_binary_equality, |a: $name, b: $name| -> Bool { (a == b).into() }, "{} == {}");
+            implement_func!(BinNotEqual, __op_binary_not_equal, |a: $name, b: $name| -> Bool { (a != b).into() }, "{} != {}");
+
+            implement_func!(BinLess, __op_binary_less, |a: $name, b: $name| -> Bool { (a < b).into() }, "{} < {}");
+            implement_func!(BinLessEq, __op_binary_less_equal, |a: $name, b: $name| -> Bool { (a <= b).into() }, "{} <= {}");
+            implement_func!(BinGreater, __op_binary_greater, |a: $name, b: $name| -> Bool { (a > b).into() }, "{} > {}");
+            implement_func!(BinGreaterEq, __op_binary_greater_equal, |a: $name, b: $name| -> Bool { (a >= b).into() }, "{} >= {}");
+		}
+	};
+}
 
-            implement_func!(BinLess, __op_binary_less, |a: $name, b: $name| -> Int { if a < b { 1 } else { 0 } }, "{} < {}");
-            implement_func!(BinLessEq, __op_binary_less_equal, |a: $name, b: $name| -> Int { if a <= b { 1 } else { 0 } }, "{} <= {}");       
-            implement_func!(BinGreater, __op_binary_greater, |a: $name, b: $name| -> Int { if a >= b { 1 } else { 0 } }, "{} > {}");
-            implement_func!(BinGreaterEq, __op_binary_greater_equal, |a: $name, b: $name| -> Int { if a >= b { 1 } else { 0 } }, "{} >= {}");
+macro_rules! implement_signed_num_ops {
+	( $name:ident ) => {
+		paste::item! {
+            implement_func!(UnNeg, __op_unary_neg, |a: $name| -> $name { -a }, "-{}");
 		}
 	};
 }
@@ -63,25 +224,121 @@ macro_rules! implement_float_num_ops {
 	};
 }
 
+// GLSL leaves integer division by zero undefined, the VM returns zero instead of crashing the host
 macro_rules! implement_integer_num_ops {
 	( $name:ident ) => {
 		paste::item! {
-            implement_func!(UnNot, __op_unary_not, |a: $name| -> $name { if a == 0 { 1 as $name } else { 0 as $name } }, "!{}");
             implement_func!(BinMul, __op_binary_mul, |a: $name, b: $name| -> $name { a.wrapping_mul(b) }, "{} * {}");
-            implement_func!(BinDiv, __op_binary_div, |a: $name, b: $name| -> $name { a.wrapping_div(b) }, "{} / {}");
+            implement_func!(BinDiv, __op_binary_div, |a: $name, b: $name| -> $name { if b == 0 { 0 } else { a.wrapping_div(b) } }, "{} / {}");
             implement_func!(BinAdd, __op_binary_add, |a: $name, b: $name| -> $name { a.wrapping_add(b) }, "{} + {}");
             implement_func!(BinSub, __op_binary_sub, |a: $name, b: $name| -> $name { a.wrapping_sub(b) }, "{} - {}");
-            implement_func!(BinAnd, __op_binary_and, |a: $name, b: $name| -> $name { if a != 0 && b != 0 { 1 } else { 0 } }, "bool({}) && bool({})");
-            implement_func!(BinOr, __op_binary_or,   |a: $name, b: $name| -> $name { if a != 0 || b != 0 { 1 } else { 0 } }, "bool({}) || bool({})");
-            implement_func!(BinXor, __op_binary_or,  |a: $name, b: $name| -> $name { unimplemented!("{}, {}", a, b) }, "bool({}) ^^ bool({})");
+            implement_func!(BinMod, __op_binary_mod, |a: $name, b: $name| -> $name { if b == 0 { 0 } else { a.wrapping_rem(b) } }, "{} % {}");
+
+            implement_func!(UnBitNot, __op_unary_bitnot, |a: $name| -> $name { !a }, "~{}");
+            implement_func!(BinBitAnd, __op_binary_bitand, |a: $name, b: $name| -> $name { a & b }, "{} & {}");
+            implement_func!(BinBitOr, __op_binary_bitor, |a: $name, b: $name| -> $name { a | b }, "{} | {}");
+            implement_func!(BinBitXor, __op_binary_bitxor, |a: $name, b: $name| -> $name { a ^ b }, "{} ^ {}");
+            implement_func!(BinShl, __op_binary_shl, |a: $name, b: $name| -> $name { a.wrapping_shl(b as u32) }, "{} << {}");
+            implement_func!(BinShr, __op_binary_shr, |a: $name, b: $name| -> $name { a.wrapping_shr(b as u32) }, "{} >> {}");
 		}
 	};
 }
 
 implement_common_num_ops!(Float);
 implement_common_num_ops!(Int);
+implement_common_num_ops!(UInt);
+implement_signed_num_ops!(Float);
+implement_signed_num_ops!(Int);
 implement_float_num_ops!(Float);
 implement_integer_num_ops!(Int);
+implement_integer_num_ops!(UInt);
+
+// `&&` and `||` are compiled to jumps so they short circuit, these are only used for type checking and GLSL
+implement_func!(BinAnd, __op_binary_and, |a: Bool, b: Bool| -> Bool { (bool::from(a) && bool::from(b)).into() }, "{} && {}");
+implement_func!(BinOr, __op_binary_or, |a: Bool, b: Bool| -> Bool { (bool::from(a) || bool::from(b)).into() }, "{} || {}");
+implement_func!(BinXor, __op_binary_xor, |a: Bool, b: Bool| -> Bool { (a != b).into() }, "{} ^^ {}");
+implement_func!(UnNot, __op_unary_not, |a: Bool| -> Bool { (!bool::from(a)).into() }, "!{}");
+implement_func!(BinEquality, __op_binary_equality, |a: Bool, b: Bool| -> Bool { (a == b).into() }, "{} == {}");
+implement_func!(BinNotEqual, __op_binary_not_equal, |a: Bool, b: Bool| -> Bool { (a != b).into() }, "{} != {}");
 
 implement_func!(Cast, int, |a: Float| -> Int { a as i32 }, "int({})");
 implement_func!(Cast, float, |a: Int| -> Float { a as f32 }, "float({})");
+implement_func!(ToUInt, uint, |a: Int| -> UInt { a as u32 }, "uint({})");
+implement_func!(ToUInt, uint, |a: Float| -> UInt { a as u32 }, "uint({})");
+implement_func!(ToInt, int, |a: UInt| -> Int { a as i32 }, "int({})");
+implement_func!(ToFloat, float, |a: UInt| -> Float { a as f32 }, "float({})");
+
+// constructor style casts, e.g. `Float(i)`, `Vec3(1.0)`, `Vec2(v)` or `Mat3(m)`
+implement_func!(FloatCtor, Float, |a: Int| -> Float { a as Float }, "float({})");
+implement_func!(FloatCtor, Float, |a: UInt| -> Float { a as Float }, "float({})");
+implement_func!(FloatCtor, Float, |a: Bool| -> Float { a.0 as Float }, "float({})");
+implement_func!(IntCtor, Int, |a: Float| -> Int { a as Int }, "int({})");
+implement_func!(IntCtor, Int, |a: UInt| -> Int { a as Int }, "int({})");
+implement_func!(IntCtor, Int, |a: Bool| -> Int { a.0 as Int }, "int({})");
+implement_func!(UIntCtor, UInt, |a: Float| -> UInt { a as UInt }, "uint({})");
+implement_func!(UIntCtor, UInt, |a: Int| -> UInt { a as UInt }, "uint({})");
+implement_func!(UIntCtor, UInt, |a: Bool| -> UInt { a.0 }, "uint({})");
+implement_func!(BoolCtor, Bool, |a: Float| -> Bool { (a != 0.0).into() }, "bool({})");
+implement_func!(BoolCtor, Bool, |a: Int| -> Bool { (a != 0).into() }, "bool({})");
+implement_func!(BoolCtor, Bool, |a: UInt| -> Bool { (a != 0).into() }, "bool({})");
+
+macro_rules! implement_vec_splat {
+	( $vec:ident, $comp:ident, $gl:literal ) => {
+		paste::item! {
+            implement_func!([<$vec Splat>], $vec, |a: $comp| -> $vec { $vec::from_fn(|_| a) }, $gl);
+		}
+	};
+}
+
+macro_rules! implement_vec_truncation {
+	( $from:ident, $to:ident, $gl:literal ) => {
+		paste::item! {
+            implement_func!([<To $to>], $to, |a: $from| -> $to { $to::from_fn(|i| a.get_elem(i)) }, $gl);
+		}
+	};
+}
+
+implement_vec_splat!(Vec2, Float, "vec2({})");
+implement_vec_splat!(Vec3, Float, "vec3({})");
+implement_vec_splat!(Vec4, Float, "vec4({})");
+implement_vec_splat!(IVec2, Int, "ivec2({})");
+implement_vec_splat!(IVec3, Int, "ivec3({})");
+implement_vec_splat!(IVec4, Int, "ivec4({})");
+implement_vec_splat!(UVec2, UInt, "uvec2({})");
+implement_vec_splat!(UVec3, UInt, "uvec3({})");
+implement_vec_splat!(UVec4, UInt, "uvec4({})");
+implement_vec_splat!(BVec2, Bool, "bvec2({})");
+implement_vec_splat!(BVec3, Bool, "bvec3({})");
+implement_vec_splat!(BVec4, Bool, "bvec4({})");
+
+implement_vec_truncation!(Vec3, Vec2, "vec2({})");
+implement_vec_truncation!(Vec4, Vec2, "vec2({})");
+implement_vec_truncation!(Vec4, Vec3, "vec3({})");
+implement_vec_truncation!(IVec3, IVec2, "ivec2({})");
+implement_vec_truncation!(IVec4, IVec2, "ivec2({})");
+implement_vec_truncation!(IVec4, IVec3, "ivec3({})");
+implement_vec_truncation!(UVec3, UVec2, "uvec2({})");
+implement_vec_truncation!(UVec4, UVec2, "uvec2({})");
+implement_vec_truncation!(UVec4, UVec3, "uvec3({})");
+implement_vec_truncation!(BVec3, BVec2, "bvec2({})");
+implement_vec_truncation!(BVec4, BVec2, "bvec2({})");
+implement_vec_truncation!(BVec4, BVec3, "bvec3({})");
+
+macro_rules! implement_mat_conversions {
+	( $mat:ident, $gl:literal, [$($from:ident),*] ) => {
+		paste::item! {
+            implement_func!([<$mat Diagonal>], $mat, |a: Float| -> $mat { $mat::diagonal(a) }, $gl);
+            $(implement_func!([<To $mat>], $mat, |a: $from| -> $mat { a.resize() }, $gl);)*
+		}
+	};
+}
+
+implement_mat_conversions!(Mat2, "mat2({})", [Mat2x3, Mat2x4, Mat3x2, Mat3, Mat3x4, Mat4x2, Mat4x3, Mat4]);
+implement_mat_conversions!(Mat2x3, "mat2x3({})", [Mat2, Mat2x4, Mat3x2, Mat3, Mat3x4, Mat4x2, Mat4x3, Mat4]);
+implement_mat_conversions!(Mat2x4, "mat2x4({})", [Mat2, Mat2x3, Mat3x2, Mat3, Mat3x4, Mat4x2, Mat4x3, Mat4]);
+implement_mat_conversions!(Mat3x2, "mat3x2({})", [Mat2, Mat2x3, Mat2x4, Mat3, Mat3x4, Mat4x2, Mat4x3, Mat4]);
+implement_mat_conversions!(Mat3, "mat3({})", [Mat2, Mat2x3, Mat2x4, Mat3x2, Mat3x4, Mat4x2, Mat4x3, Mat4]);
+implement_mat_conversions!(Mat3x4, "mat3x4({})", [Mat2, Mat2x3, Mat2x4, Mat3x2, Mat3, Mat4x2, Mat4x3, Mat4]);
+implement_mat_conversions!(Mat4x2, "mat4x2({})", [Mat2, Mat2x3, Mat2x4, Mat3x2, Mat3, Mat3x4, Mat4x3, Mat4]);
+implement_mat_conversions!(Mat4x3, "mat4x3({})", [Mat2, Mat2x3, Mat2x4, Mat3x2, Mat3, Mat3x4, Mat4x2, Mat4]);
+implement_mat_conversions!(Mat4, "mat4({})", [Mat2, Mat2x3, Mat2x4, Mat3x2, Mat3, Mat3x4, Mat4x2, Mat4x3]);
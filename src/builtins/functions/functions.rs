@@ -2,6 +2,8 @@ pub const FUNCTIONS: &[&dyn BuiltInCallable] = &[
 	&Vec4FloatBinMul,
 	&FloatVec4BinMul,
 	&Vec4FloatBinDiv,
+	&Vec4Vec4BinMul,
+	&Vec4Vec4BinDiv,
 	&Vec4Vec4BinAdd,
 	&Vec4Vec4BinSub,
 	&Vec4BinNeg,
@@ -10,6 +12,8 @@ pub const FUNCTIONS: &[&dyn BuiltInCallable] = &[
 	&Vec3FloatBinMul,
 	&FloatVec3BinMul,
 	&Vec3FloatBinDiv,
+	&Vec3Vec3BinMul,
+	&Vec3Vec3BinDiv,
 	&Vec3Vec3BinAdd,
 	&Vec3Vec3BinSub,
 	&Vec3BinNeg,
@@ -18,11 +22,190 @@ pub const FUNCTIONS: &[&dyn BuiltInCallable] = &[
 	&Vec2FloatBinMul,
 	&FloatVec2BinMul,
 	&Vec2FloatBinDiv,
+	&Vec2Vec2BinMul,
+	&Vec2Vec2BinDiv,
 	&Vec2Vec2BinAdd,
 	&Vec2Vec2BinSub,
 	&Vec2BinNeg,
 	&Vec2Vec2BinEquality,
 	&Vec2Vec2BinNotEqual,
+	&IVec4IntBinMul,
+	&IntIVec4BinMul,
+	&IVec4IntBinDiv,
+	&IVec4IVec4BinMul,
+	&IVec4IVec4BinDiv,
+	&IVec4IVec4BinAdd,
+	&IVec4IVec4BinSub,
+	&IVec4IVec4BinEquality,
+	&IVec4IVec4BinNotEqual,
+	&IVec3IntBinMul,
+	&IntIVec3BinMul,
+	&IVec3IntBinDiv,
+	&IVec3IVec3BinMul,
+	&IVec3IVec3BinDiv,
+	&IVec3IVec3BinAdd,
+	&IVec3IVec3BinSub,
+	&IVec3IVec3BinEquality,
+	&IVec3IVec3BinNotEqual,
+	&IVec2IntBinMul,
+	&IntIVec2BinMul,
+	&IVec2IntBinDiv,
+	&IVec2IVec2BinMul,
+	&IVec2IVec2BinDiv,
+	&IVec2IVec2BinAdd,
+	&IVec2IVec2BinSub,
+	&IVec2IVec2BinEquality,
+	&IVec2IVec2BinNotEqual,
+	&UVec4UIntBinMul,
+	&UIntUVec4BinMul,
+	&UVec4UIntBinDiv,
+	&UVec4UVec4BinMul,
+	&UVec4UVec4BinDiv,
+	&UVec4UVec4BinAdd,
+	&UVec4UVec4BinSub,
+	&UVec4UVec4BinEquality,
+	&UVec4UVec4BinNotEqual,
+	&UVec3UIntBinMul,
+	&UIntUVec3BinMul,
+	&UVec3UIntBinDiv,
+	&UVec3UVec3BinMul,
+	&UVec3UVec3BinDiv,
+	&UVec3UVec3BinAdd,
+	&UVec3UVec3BinSub,
+	&UVec3UVec3BinEquality,
+	&UVec3UVec3BinNotEqual,
+	&UVec2UIntBinMul,
+	&UIntUVec2BinMul,
+	&UVec2UIntBinDiv,
+	&UVec2UVec2BinMul,
+	&UVec2UVec2BinDiv,
+	&UVec2UVec2BinAdd,
+	&UVec2UVec2BinSub,
+	&UVec2UVec2BinEquality,
+	&UVec2UVec2BinNotEqual,
+	&IVec4BinNeg,
+	&IVec3BinNeg,
+	&IVec2BinNeg,
+	&Vec4Vec4LessThan,
+	&Vec4Vec4LessThanEqual,
+	&Vec4Vec4GreaterThan,
+	&Vec4Vec4GreaterThanEqual,
+	&Vec4Vec4Equal,
+	&Vec4Vec4NotEqual,
+	&Vec3Vec3LessThan,
+	&Vec3Vec3LessThanEqual,
+	&Vec3Vec3GreaterThan,
+	&Vec3Vec3GreaterThanEqual,
+	&Vec3Vec3Equal,
+	&Vec3Vec3NotEqual,
+	&Vec2Vec2LessThan,
+	&Vec2Vec2LessThanEqual,
+	&Vec2Vec2GreaterThan,
+	&Vec2Vec2GreaterThanEqual,
+	&Vec2Vec2Equal,
+	&Vec2Vec2NotEqual,
+	&IVec4IVec4LessThan,
+	&IVec4IVec4LessThanEqual,
+	&IVec4IVec4GreaterThan,
+	&IVec4IVec4GreaterThanEqual,
+	&IVec4IVec4Equal,
+	&IVec4IVec4NotEqual,
+	&IVec3IVec3LessThan,
+	&IVec3IVec3LessThanEqual,
+	&IVec3IVec3GreaterThan,
+	&IVec3IVec3GreaterThanEqual,
+	&IVec3IVec3Equal,
+	&IVec3IVec3NotEqual,
+	&IVec2IVec2LessThan,
+	&IVec2IVec2LessThanEqual,
+	&IVec2IVec2GreaterThan,
+	&IVec2IVec2GreaterThanEqual,
+	&IVec2IVec2Equal,
+	&IVec2IVec2NotEqual,
+	&UVec4UVec4LessThan,
+	&UVec4UVec4LessThanEqual,
+	&UVec4UVec4GreaterThan,
+	&UVec4UVec4GreaterThanEqual,
+	&UVec4UVec4Equal,
+	&UVec4UVec4NotEqual,
+	&UVec3UVec3LessThan,
+	&UVec3UVec3LessThanEqual,
+	&UVec3UVec3GreaterThan,
+	&UVec3UVec3GreaterThanEqual,
+	&UVec3UVec3Equal,
+	&UVec3UVec3NotEqual,
+	&UVec2UVec2LessThan,
+	&UVec2UVec2LessThanEqual,
+	&UVec2UVec2GreaterThan,
+	&UVec2UVec2GreaterThanEqual,
+	&UVec2UVec2Equal,
+	&UVec2UVec2NotEqual,
+	&BVec4BVec4BinEquality,
+	&BVec4BVec4BinNotEqual,
+	&BVec4BVec4Equal,
+	&BVec4BVec4NotEqual,
+	&BVec4Any,
+	&BVec4All,
+	&BVec4Not,
+	&BVec3BVec3BinEquality,
+	&BVec3BVec3BinNotEqual,
+	&BVec3BVec3Equal,
+	&BVec3BVec3NotEqual,
+	&BVec3Any,
+	&BVec3All,
+	&BVec3Not,
+	&BVec2BVec2BinEquality,
+	&BVec2BVec2BinNotEqual,
+	&BVec2BVec2Equal,
+	&BVec2BVec2NotEqual,
+	&BVec2Any,
+	&BVec2All,
+	&BVec2Not,
+	&IntIntIVec2Ctor,
+	&IntIntIntIVec3Ctor,
+	&IntIntIntIntIVec4Ctor,
+	&UIntUIntUVec2Ctor,
+	&UIntUIntUIntUVec3Ctor,
+	&UIntUIntUIntUIntUVec4Ctor,
+	&BoolBoolBVec2Ctor,
+	&BoolBoolBoolBVec3Ctor,
+	&BoolBoolBoolBoolBVec4Ctor,
+	&IVec2ToVec2,
+	&UVec2ToVec2,
+	&BVec2ToVec2,
+	&Vec2ToIVec2,
+	&UVec2ToIVec2,
+	&BVec2ToIVec2,
+	&Vec2ToUVec2,
+	&IVec2ToUVec2,
+	&BVec2ToUVec2,
+	&Vec2ToBVec2,
+	&IVec2ToBVec2,
+	&UVec2ToBVec2,
+	&IVec3ToVec3,
+	&UVec3ToVec3,
+	&BVec3ToVec3,
+	&Vec3ToIVec3,
+	&UVec3ToIVec3,
+	&BVec3ToIVec3,
+	&Vec3ToUVec3,
+	&IVec3ToUVec3,
+	&BVec3ToUVec3,
+	&Vec3ToBVec3,
+	&IVec3ToBVec3,
+	&UVec3ToBVec3,
+	&IVec4ToVec4,
+	&UVec4ToVec4,
+	&BVec4ToVec4,
+	&Vec4ToIVec4,
+	&UVec4ToIVec4,
+	&BVec4ToIVec4,
+	&Vec4ToUVec4,
+	&IVec4ToUVec4,
+	&BVec4ToUVec4,
+	&Vec4ToBVec4,
+	&IVec4ToBVec4,
+	&UVec4ToBVec4,
 	&Vec2Constructor,
 	&Vec3Constructor,
 	&Vec4Constructor,
@@ -44,34 +227,226 @@ pub const FUNCTIONS: &[&dyn BuiltInCallable] = &[
 	&Mat4x3VectorConstructor,
 	&Mat4Constructor,
 	&Mat4VectorConstructor,
-	&FloatUnNeg,
+	&Mat2Vec2BinMul,
+	&Vec2Mat2BinMul,
+	&Mat2x3Vec2BinMul,
+	&Vec3Mat2x3BinMul,
+	&Mat2x4Vec2BinMul,
+	&Vec4Mat2x4BinMul,
+	&Mat3x2Vec3BinMul,
+	&Vec2Mat3x2BinMul,
+	&Mat3Vec3BinMul,
+	&Vec3Mat3BinMul,
+	&Mat3x4Vec3BinMul,
+	&Vec4Mat3x4BinMul,
+	&Mat4x2Vec4BinMul,
+	&Vec2Mat4x2BinMul,
+	&Mat4x3Vec4BinMul,
+	&Vec3Mat4x3BinMul,
+	&Mat4Vec4BinMul,
+	&Vec4Mat4BinMul,
+	&Mat2Mat2BinMul,
+	&Mat2Mat3x2BinMul,
+	&Mat2Mat4x2BinMul,
+	&Mat2x3Mat2BinMul,
+	&Mat2x3Mat3x2BinMul,
+	&Mat2x3Mat4x2BinMul,
+	&Mat2x4Mat2BinMul,
+	&Mat2x4Mat3x2BinMul,
+	&Mat2x4Mat4x2BinMul,
+	&Mat3x2Mat2x3BinMul,
+	&Mat3x2Mat3BinMul,
+	&Mat3x2Mat4x3BinMul,
+	&Mat3Mat2x3BinMul,
+	&Mat3Mat3BinMul,
+	&Mat3Mat4x3BinMul,
+	&Mat3x4Mat2x3BinMul,
+	&Mat3x4Mat3BinMul,
+	&Mat3x4Mat4x3BinMul,
+	&Mat4x2Mat2x4BinMul,
+	&Mat4x2Mat3x4BinMul,
+	&Mat4x2Mat4BinMul,
+	&Mat4x3Mat2x4BinMul,
+	&Mat4x3Mat3x4BinMul,
+	&Mat4x3Mat4BinMul,
+	&Mat4Mat2x4BinMul,
+	&Mat4Mat3x4BinMul,
+	&Mat4Mat4BinMul,
 	&FloatFloatBinEquality,
 	&FloatFloatBinNotEqual,
 	&FloatFloatBinLess,
 	&FloatFloatBinLessEq,
 	&FloatFloatBinGreater,
 	&FloatFloatBinGreaterEq,
-	&IntUnNeg,
 	&IntIntBinEquality,
 	&IntIntBinNotEqual,
 	&IntIntBinLess,
 	&IntIntBinLessEq,
 	&IntIntBinGreater,
 	&IntIntBinGreaterEq,
+	&UIntUIntBinEquality,
+	&UIntUIntBinNotEqual,
+	&UIntUIntBinLess,
+	&UIntUIntBinLessEq,
+	&UIntUIntBinGreater,
+	&UIntUIntBinGreaterEq,
+	&FloatUnNeg,
+	&IntUnNeg,
 	&FloatFloatBinMul,
 	&FloatFloatBinDiv,
 	&FloatFloatBinAdd,
 	&FloatFloatBinSub,
-	&IntUnNot,
 	&IntIntBinMul,
 	&IntIntBinDiv,
 	&IntIntBinAdd,
 	&IntIntBinSub,
-	&IntIntBinAnd,
-	&IntIntBinOr,
-	&IntIntBinXor,
+	&IntIntBinMod,
+	&IntUnBitNot,
+	&IntIntBinBitAnd,
+	&IntIntBinBitOr,
+	&IntIntBinBitXor,
+	&IntIntBinShl,
+	&IntIntBinShr,
+	&UIntUIntBinMul,
+	&UIntUIntBinDiv,
+	&UIntUIntBinAdd,
+	&UIntUIntBinSub,
+	&UIntUIntBinMod,
+	&UIntUnBitNot,
+	&UIntUIntBinBitAnd,
+	&UIntUIntBinBitOr,
+	&UIntUIntBinBitXor,
+	&UIntUIntBinShl,
+	&UIntUIntBinShr,
+	&BoolBoolBinAnd,
+	&BoolBoolBinOr,
+	&BoolBoolBinXor,
+	&BoolUnNot,
+	&BoolBoolBinEquality,
+	&BoolBoolBinNotEqual,
 	&FloatCast,
 	&IntCast,
+	&IntToUInt,
+	&FloatToUInt,
+	&UIntToInt,
+	&UIntToFloat,
+	&IntFloatCtor,
+	&UIntFloatCtor,
+	&BoolFloatCtor,
+	&FloatIntCtor,
+	&UIntIntCtor,
+	&BoolIntCtor,
+	&FloatUIntCtor,
+	&IntUIntCtor,
+	&BoolUIntCtor,
+	&FloatBoolCtor,
+	&IntBoolCtor,
+	&UIntBoolCtor,
+	&FloatVec2Splat,
+	&FloatVec3Splat,
+	&FloatVec4Splat,
+	&IntIVec2Splat,
+	&IntIVec3Splat,
+	&IntIVec4Splat,
+	&UIntUVec2Splat,
+	&UIntUVec3Splat,
+	&UIntUVec4Splat,
+	&BoolBVec2Splat,
+	&BoolBVec3Splat,
+	&BoolBVec4Splat,
+	&Vec3ToVec2,
+	&Vec4ToVec2,
+	&Vec4ToVec3,
+	&IVec3ToIVec2,
+	&IVec4ToIVec2,
+	&IVec4ToIVec3,
+	&UVec3ToUVec2,
+	&UVec4ToUVec2,
+	&UVec4ToUVec3,
+	&BVec3ToBVec2,
+	&BVec4ToBVec2,
+	&BVec4ToBVec3,
+	&FloatMat2Diagonal,
+	&Mat2x3ToMat2,
+	&Mat2x4ToMat2,
+	&Mat3x2ToMat2,
+	&Mat3ToMat2,
+	&Mat3x4ToMat2,
+	&Mat4x2ToMat2,
+	&Mat4x3ToMat2,
+	&Mat4ToMat2,
+	&FloatMat2x3Diagonal,
+	&Mat2ToMat2x3,
+	&Mat2x4ToMat2x3,
+	&Mat3x2ToMat2x3,
+	&Mat3ToMat2x3,
+	&Mat3x4ToMat2x3,
+	&Mat4x2ToMat2x3,
+	&Mat4x3ToMat2x3,
+	&Mat4ToMat2x3,
+	&FloatMat2x4Diagonal,
+	&Mat2ToMat2x4,
+	&Mat2x3ToMat2x4,
+	&Mat3x2ToMat2x4,
+	&Mat3ToMat2x4,
+	&Mat3x4ToMat2x4,
+	&Mat4x2ToMat2x4,
+	&Mat4x3ToMat2x4,
+	&Mat4ToMat2x4,
+	&FloatMat3x2Diagonal,
+	&Mat2ToMat3x2,
+	&Mat2x3ToMat3x2,
+	&Mat2x4ToMat3x2,
+	&Mat3ToMat3x2,
+	&Mat3x4ToMat3x2,
+	&Mat4x2ToMat3x2,
+	&Mat4x3ToMat3x2,
+	&Mat4ToMat3x2,
+	&FloatMat3Diagonal,
+	&Mat2ToMat3,
+	&Mat2x3ToMat3,
+	&Mat2x4ToMat3,
+	&Mat3x2ToMat3,
+	&Mat3x4ToMat3,
+	&Mat4x2ToMat3,
+	&Mat4x3ToMat3,
+	&Mat4ToMat3,
+	&FloatMat3x4Diagonal,
+	&Mat2ToMat3x4,
+	&Mat2x3ToMat3x4,
+	&Mat2x4ToMat3x4,
+	&Mat3x2ToMat3x4,
+	&Mat3ToMat3x4,
+	&Mat4x2ToMat3x4,
+	&Mat4x3ToMat3x4,
+	&Mat4ToMat3x4,
+	&FloatMat4x2Diagonal,
+	&Mat2ToMat4x2,
+	&Mat2x3ToMat4x2,
+	&Mat2x4ToMat4x2,
+	&Mat3x2ToMat4x2,
+	&Mat3ToMat4x2,
+	&Mat3x4ToMat4x2,
+	&Mat4x3ToMat4x2,
+	&Mat4ToMat4x2,
+	&FloatMat4x3Diagonal,
+	&Mat2ToMat4x3,
+	&Mat2x3ToMat4x3,
+	&Mat2x4ToMat4x3,
+	&Mat3x2ToMat4x3,
+	&Mat3ToMat4x3,
+	&Mat3x4ToMat4x3,
+	&Mat4x2ToMat4x3,
+	&Mat4ToMat4x3,
+	&FloatMat4Diagonal,
+	&Mat2ToMat4,
+	&Mat2x3ToMat4,
+	&Mat2x4ToMat4,
+	&Mat3x2ToMat4,
+	&Mat3ToMat4,
+	&Mat3x4ToMat4,
+	&Mat4x2ToMat4,
+	&Mat4x3ToMat4,
 	&Vec2IntElem,
 	&Vec2Length,
 	&Vec2Normalize,
@@ -112,4 +487,106 @@ pub const FUNCTIONS: &[&dyn BuiltInCallable] = &[
 	&FloatFloatLogn,
 	&FloatFloatMin,
 	&FloatFloatMax,
+	&FloatInversesqrt,
+	&FloatFloatMod,
+	&FloatFloatStep,
+	&FloatFloatFloatClamp,
+	&FloatFloatFloatMix,
+	&FloatFloatFloatSmoothstep,
+	&FloatFloatFloatFma,
+	&Vec2Abs,
+	&Vec2Sign,
+	&Vec2Floor,
+	&Vec2Ceil,
+	&Vec2Fract,
+	&Vec2Sqrt,
+	&Vec2Inversesqrt,
+	&Vec2Vec2Min,
+	&Vec2FloatMin,
+	&Vec2Vec2Max,
+	&Vec2FloatMax,
+	&Vec2Vec2Mod,
+	&Vec2FloatMod,
+	&Vec2Vec2Step,
+	&FloatVec2Step,
+	&Vec2Vec2Vec2Clamp,
+	&Vec2FloatFloatClamp,
+	&Vec2Vec2Vec2Mix,
+	&Vec2Vec2FloatMix,
+	&Vec2Vec2Vec2Smoothstep,
+	&FloatFloatVec2Smoothstep,
+	&Vec2Vec2Vec2Fma,
+	&Vec2Vec2Reflect,
+	&Vec2Vec2FloatRefract,
+	&Vec2Vec2Vec2Faceforward,
+	&Vec3Abs,
+	&Vec3Sign,
+	&Vec3Floor,
+	&Vec3Ceil,
+	&Vec3Fract,
+	&Vec3Sqrt,
+	&Vec3Inversesqrt,
+	&Vec3Vec3Min,
+	&Vec3FloatMin,
+	&Vec3Vec3Max,
+	&Vec3FloatMax,
+	&Vec3Vec3Mod,
+	&Vec3FloatMod,
+	&Vec3Vec3Step,
+	&FloatVec3Step,
+	&Vec3Vec3Vec3Clamp,
+	&Vec3FloatFloatClamp,
+	&Vec3Vec3Vec3Mix,
+	&Vec3Vec3FloatMix,
+	&Vec3Vec3Vec3Smoothstep,
+	&FloatFloatVec3Smoothstep,
+	&Vec3Vec3Vec3Fma,
+	&Vec3Vec3Reflect,
+	&Vec3Vec3FloatRefract,
+	&Vec3Vec3Vec3Faceforward,
+	&Vec4Abs,
+	&Vec4Sign,
+	&Vec4Floor,
+	&Vec4Ceil,
+	&Vec4Fract,
+	&Vec4Sqrt,
+	&Vec4Inversesqrt,
+	&Vec4Vec4Min,
+	&Vec4FloatMin,
+	&Vec4Vec4Max,
+	&Vec4FloatMax,
+	&Vec4Vec4Mod,
+	&Vec4FloatMod,
+	&Vec4Vec4Step,
+	&FloatVec4Step,
+	&Vec4Vec4Vec4Clamp,
+	&Vec4FloatFloatClamp,
+	&Vec4Vec4Vec4Mix,
+	&Vec4Vec4FloatMix,
+	&Vec4Vec4Vec4Smoothstep,
+	&FloatFloatVec4Smoothstep,
+	&Vec4Vec4Vec4Fma,
+	&Vec4Vec4Reflect,
+	&Vec4Vec4FloatRefract,
+	&Vec4Vec4Vec4Faceforward,
+	&Vec3Vec3Cross,
+	&Mat2Transpose,
+	&Mat2x3Transpose,
+	&Mat2x4Transpose,
+	&Mat3x2Transpose,
+	&Mat3Transpose,
+	&Mat3x4Transpose,
+	&Mat4x2Transpose,
+	&Mat4x3Transpose,
+	&Mat4Transpose,
+	&Mat2Determinant,
+	&Mat2Inverse,
+	&Mat3Determinant,
+	&Mat3Inverse,
+	&Mat4Determinant,
+	&Mat4Inverse,
+	&Sampler2DVec2Texture,
+	&Sampler2DVec2FloatTextureLod,
+	&Sampler2DIVec2IntTexelFetch,
+	&Sampler2DIntTextureSize,
 ];
\ No newline at end of file
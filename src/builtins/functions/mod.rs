@@ -4,5 +4,7 @@ pub mod basics;
 pub use basics::*;
 pub mod stdlib;
 pub use stdlib::*;
+pub mod texture;
+pub use texture::*;
 
 include!("functions.rs");
@@ -19,12 +19,14 @@ implement_vec_funcs!(Vec4);
 macro_rules! implement_common_num_funcs {
     ( $name:ident ) => {
         implement_func!(Abs, abs, |a: $name| -> $name { a.abs() }, "abs({})");
-        implement_func!(Sign, sign, |a: $name| -> $name { a.signum() }, "sign({})");
     }
 }
 implement_common_num_funcs!(Float);
 implement_common_num_funcs!(Int);
 
+implement_func!(Sign, sign, |a: Float| -> Float { sign(a) }, "sign({})");
+implement_func!(Sign, sign, |a: Int| -> Int { a.signum() }, "sign({})");
+
 macro_rules! implement_float_func { 
     ( $func:ident, $impl:expr, $glimpl:literal, 1) => {
         paste::item! {
@@ -53,9 +55,146 @@ implement_float_func!(Log2, f32::log2, "log2({})", 1);
 implement_float_func!(Sqrt, f32::sqrt, "sqrt({})", 1);
 implement_float_func!(Floor, f32::floor, "floor({})", 1);
 implement_float_func!(Ceil, f32::ceil, "ceil({})", 1);
-implement_float_func!(Fract, f32::fract, "fract({})", 1);
+implement_float_func!(Fract, fract, "fract({})", 1);
 implement_float_func!(Atan2, f32::atan2, "atan2({}, {})", 2);
 implement_float_func!(Pow, f32::pow, "pow({}, {})", 2);
 implement_float_func!(Logn, f32::log, "logn({}, {})", 2);
 implement_float_func!(Min, f32::min, "min({}, {})", 2);
-implement_float_func!(Max, f32::max, "max({}, {})", 2);
\ No newline at end of file
+implement_float_func!(Max, f32::max, "max({}, {})", 2);
+
+macro_rules! implement_mat_funcs {
+    ( $name:ident, $transposed:ident ) => {
+        implement_func!(Transpose, transpose, |a: $name| -> $transposed { a.transpose() }, "transpose({})");
+    }
+}
+implement_mat_funcs!(Mat2, Mat2);
+implement_mat_funcs!(Mat2x3, Mat3x2);
+implement_mat_funcs!(Mat2x4, Mat4x2);
+implement_mat_funcs!(Mat3x2, Mat2x3);
+implement_mat_funcs!(Mat3, Mat3);
+implement_mat_funcs!(Mat3x4, Mat4x3);
+implement_mat_funcs!(Mat4x2, Mat2x4);
+implement_mat_funcs!(Mat4x3, Mat3x4);
+implement_mat_funcs!(Mat4, Mat4);
+
+macro_rules! implement_square_mat_funcs {
+    ( $name:ident ) => {
+        implement_func!(Determinant, determinant, |a: $name| -> Float { a.determinant() }, "determinant({})");
+        implement_func!(Inverse, inverse, |a: $name| -> $name { a.inverse() }, "inverse({})");
+    }
+}
+implement_square_mat_funcs!(Mat2);
+implement_square_mat_funcs!(Mat3);
+implement_square_mat_funcs!(Mat4);
+
+// Scalar implementations of the GLSL functions, following the definitions in the GLSL specification. Unlike their
+// rust counterparts, `sign(0.0)` is `0.0` and `fract` of a negative number is positive.
+fn sign(x: f32) -> f32 {
+    if x > 0.0 {
+        1.0
+    } else if x < 0.0 {
+        -1.0
+    } else {
+        0.0
+    }
+}
+
+fn fract(x: f32) -> f32 {
+    x - x.floor()
+}
+
+fn inversesqrt(x: f32) -> f32 {
+    1.0 / x.sqrt()
+}
+
+fn clamp(x: f32, min: f32, max: f32) -> f32 {
+    x.max(min).min(max)
+}
+
+fn mix(x: f32, y: f32, a: f32) -> f32 {
+    x * (1.0 - a) + y * a
+}
+
+fn step(edge: f32, x: f32) -> f32 {
+    if x < edge {
+        0.0
+    } else {
+        1.0
+    }
+}
+
+fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32 {
+    let t = clamp((x - edge0) / (edge1 - edge0), 0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+fn modulo(x: f32, y: f32) -> f32 {
+    x - y * (x / y).floor()
+}
+
+implement_float_func!(Inversesqrt, inversesqrt, "inversesqrt({})", 1);
+implement_func!(Mod, r#mod, |x: Float, y: Float| -> Float { modulo(x, y) }, "mod({}, {})");
+implement_float_func!(Step, step, "step({}, {})", 2);
+implement_func!(Clamp, clamp, |x: Float, min: Float, max: Float| -> Float { clamp(x, min, max) }, "clamp({}, {}, {})");
+implement_func!(Mix, mix, |x: Float, y: Float, a: Float| -> Float { mix(x, y, a) }, "mix({}, {}, {})");
+implement_func!(Smoothstep, smoothstep, |e0: Float, e1: Float, x: Float| -> Float { smoothstep(e0, e1, x) }, "smoothstep({}, {}, {})");
+// `fma` only exists since GLSL 4.0
+implement_func!(Fma, fma, |a: Float, b: Float, c: Float| -> Float { a.mul_add(b, c) }, "(({}) * ({}) + ({}))");
+
+macro_rules! implement_vec_math_funcs {
+    ( $name:ident ) => {
+        implement_func!(Abs, abs, |a: $name| -> $name { a.map(f32::abs) }, "abs({})");
+        implement_func!(Sign, sign, |a: $name| -> $name { a.map(sign) }, "sign({})");
+        implement_func!(Floor, floor, |a: $name| -> $name { a.map(f32::floor) }, "floor({})");
+        implement_func!(Ceil, ceil, |a: $name| -> $name { a.map(f32::ceil) }, "ceil({})");
+        implement_func!(Fract, fract, |a: $name| -> $name { a.map(fract) }, "fract({})");
+        implement_func!(Sqrt, sqrt, |a: $name| -> $name { a.map(f32::sqrt) }, "sqrt({})");
+        implement_func!(Inversesqrt, inversesqrt, |a: $name| -> $name { a.map(inversesqrt) }, "inversesqrt({})");
+
+        implement_func!(Min, min, |a: $name, b: $name| -> $name { a.zip_with(b, f32::min) }, "min({}, {})");
+        implement_func!(Min, min, |a: $name, b: Float| -> $name { a.map(|x| x.min(b)) }, "min({}, {})");
+        implement_func!(Max, max, |a: $name, b: $name| -> $name { a.zip_with(b, f32::max) }, "max({}, {})");
+        implement_func!(Max, max, |a: $name, b: Float| -> $name { a.map(|x| x.max(b)) }, "max({}, {})");
+        implement_func!(Mod, r#mod, |a: $name, b: $name| -> $name { a.zip_with(b, modulo) }, "mod({}, {})");
+        implement_func!(Mod, r#mod, |a: $name, b: Float| -> $name { a.map(|x| modulo(x, b)) }, "mod({}, {})");
+        implement_func!(Step, step, |edge: $name, x: $name| -> $name { edge.zip_with(x, step) }, "step({}, {})");
+        implement_func!(Step, step, |edge: Float, x: $name| -> $name { x.map(|x| step(edge, x)) }, "step({}, {})");
+
+        implement_func!(Clamp, clamp, |x: $name, min: $name, max: $name| -> $name {
+            $name::from_fn(|i| clamp(x.get_elem(i), min.get_elem(i), max.get_elem(i)))
+        }, "clamp({}, {}, {})");
+        implement_func!(Clamp, clamp, |x: $name, min: Float, max: Float| -> $name { x.map(|x| clamp(x, min, max)) }, "clamp({}, {}, {})");
+        implement_func!(Mix, mix, |x: $name, y: $name, a: $name| -> $name {
+            $name::from_fn(|i| mix(x.get_elem(i), y.get_elem(i), a.get_elem(i)))
+        }, "mix({}, {}, {})");
+        implement_func!(Mix, mix, |x: $name, y: $name, a: Float| -> $name { x.zip_with(y, |x, y| mix(x, y, a)) }, "mix({}, {}, {})");
+        implement_func!(Smoothstep, smoothstep, |e0: $name, e1: $name, x: $name| -> $name {
+            $name::from_fn(|i| smoothstep(e0.get_elem(i), e1.get_elem(i), x.get_elem(i)))
+        }, "smoothstep({}, {}, {})");
+        implement_func!(Smoothstep, smoothstep, |e0: Float, e1: Float, x: $name| -> $name { x.map(|x| smoothstep(e0, e1, x)) }, "smoothstep({}, {}, {})");
+        implement_func!(Fma, fma, |a: $name, b: $name, c: $name| -> $name {
+            $name::from_fn(|i| a.get_elem(i).mul_add(b.get_elem(i), c.get_elem(i)))
+        }, "(({}) * ({}) + ({}))");
+
+        implement_func!(Reflect, reflect, |i: $name, n: $name| -> $name { i - n * (2.0 * n.dot(i)) }, "reflect({}, {})");
+        implement_func!(Refract, refract, |i: $name, n: $name, eta: Float| -> $name {
+            let d = n.dot(i);
+            let k = 1.0 - eta * eta * (1.0 - d * d);
+            if k < 0.0 {
+                $name::default()
+            } else {
+                i * eta - n * (eta * d + k.sqrt())
+            }
+        }, "refract({}, {}, {})");
+        implement_func!(Faceforward, faceforward, |n: $name, i: $name, nref: $name| -> $name {
+            if nref.dot(i) < 0.0 { n } else { n * -1.0 }
+        }, "faceforward({}, {}, {})");
+    }
+}
+implement_vec_math_funcs!(Vec2);
+implement_vec_math_funcs!(Vec3);
+implement_vec_math_funcs!(Vec4);
+
+implement_func!(Cross, cross, |a: Vec3, b: Vec3| -> Vec3 {
+    Vec3::new(a.y() * b.z() - a.z() * b.y(), a.z() * b.x() - a.x() * b.z(), a.x() * b.y() - a.y() * b.x())
+}, "cross({}, {})");
@@ -0,0 +1,135 @@
+use crate::glsl::{compiler::GenerateGLSL, BuiltInCallableGLSL};
+use crate::{ast::TypeKind, builtins::*, vm::VirtualMachine};
+
+// The texture functions need access to the textures bound to the VM, so unlike the other builtins they can't be
+// generated by `implement_func!`.
+
+/// The color sampled from a sampler without a bound texture.
+fn unbound() -> Vec4 {
+	Vec4::from_arr([0.0, 0.0, 0.0, 1.0])
+}
+
+pub struct Sampler2DVec2Texture;
+
+impl BuiltInCallable for Sampler2DVec2Texture {
+	fn ident(&self) -> &str {
+		"texture"
+	}
+
+	fn vm_impl(&self, vm: &mut VirtualMachine) {
+		let uv = unsafe { vm.pop_stack::<Vec2>() };
+		let sampler = unsafe { vm.pop_stack::<Sampler2D>() };
+
+		// there are no derivatives to select a level of detail from, so the base level is sampled
+		let rv = vm.texture(sampler).map_or_else(unbound, |t| t.sample(uv, 0.0));
+		vm.push_stack(rv);
+	}
+
+	fn return_type(&self) -> TypeKind {
+		Vec4::type_kind()
+	}
+
+	fn arg_types(&self) -> Vec<TypeKind> {
+		vec![Sampler2D::type_kind(), Vec2::type_kind()]
+	}
+}
+
+impl BuiltInCallableGLSL for Sampler2DVec2Texture {
+	fn generate(&self, _g: &mut GenerateGLSL, args: Vec<String>) -> String {
+		format!("texture({}, {})", args[0], args[1])
+	}
+}
+
+pub struct Sampler2DVec2FloatTextureLod;
+
+impl BuiltInCallable for Sampler2DVec2FloatTextureLod {
+	fn ident(&self) -> &str {
+		"textureLod"
+	}
+
+	fn vm_impl(&self, vm: &mut VirtualMachine) {
+		let lod = unsafe { vm.pop_stack::<Float>() };
+		let uv = unsafe { vm.pop_stack::<Vec2>() };
+		let sampler = unsafe { vm.pop_stack::<Sampler2D>() };
+
+		let rv = vm.texture(sampler).map_or_else(unbound, |t| t.sample(uv, lod));
+		vm.push_stack(rv);
+	}
+
+	fn return_type(&self) -> TypeKind {
+		Vec4::type_kind()
+	}
+
+	fn arg_types(&self) -> Vec<TypeKind> {
+		vec![Sampler2D::type_kind(), Vec2::type_kind(), Float::type_kind()]
+	}
+}
+
+impl BuiltInCallableGLSL for Sampler2DVec2FloatTextureLod {
+	fn generate(&self, _g: &mut GenerateGLSL, args: Vec<String>) -> String {
+		format!("textureLod({}, {}, {})", args[0], args[1], args[2])
+	}
+}
+
+pub struct Sampler2DIVec2IntTexelFetch;
+
+impl BuiltInCallable for Sampler2DIVec2IntTexelFetch {
+	fn ident(&self) -> &str {
+		"texelFetch"
+	}
+
+	fn vm_impl(&self, vm: &mut VirtualMachine) {
+		let level = unsafe { vm.pop_stack::<Int>() };
+		let coord = unsafe { vm.pop_stack::<IVec2>() };
+		let sampler = unsafe { vm.pop_stack::<Sampler2D>() };
+
+		let rv = vm
+			.texture(sampler)
+			.map_or_else(unbound, |t| t.fetch(coord.get_elem(0), coord.get_elem(1), level));
+		vm.push_stack(rv);
+	}
+
+	fn return_type(&self) -> TypeKind {
+		Vec4::type_kind()
+	}
+
+	fn arg_types(&self) -> Vec<TypeKind> {
+		vec![Sampler2D::type_kind(), IVec2::type_kind(), Int::type_kind()]
+	}
+}
+
+impl BuiltInCallableGLSL for Sampler2DIVec2IntTexelFetch {
+	fn generate(&self, _g: &mut GenerateGLSL, args: Vec<String>) -> String {
+		format!("texelFetch({}, {}, {})", args[0], args[1], args[2])
+	}
+}
+
+pub struct Sampler2DIntTextureSize;
+
+impl BuiltInCallable for Sampler2DIntTextureSize {
+	fn ident(&self) -> &str {
+		"textureSize"
+	}
+
+	fn vm_impl(&self, vm: &mut VirtualMachine) {
+		let level = unsafe { vm.pop_stack::<Int>() };
+		let sampler = unsafe { vm.pop_stack::<Sampler2D>() };
+
+		let rv = vm.texture(sampler).map(|t| t.size(level)).unwrap_or_default();
+		vm.push_stack(rv);
+	}
+
+	fn return_type(&self) -> TypeKind {
+		IVec2::type_kind()
+	}
+
+	fn arg_types(&self) -> Vec<TypeKind> {
+		vec![Sampler2D::type_kind(), Int::type_kind()]
+	}
+}
+
+impl BuiltInCallableGLSL for Sampler2DIntTextureSize {
+	fn generate(&self, _g: &mut GenerateGLSL, args: Vec<String>) -> String {
+		format!("textureSize({}, {})", args[0], args[1])
+	}
+}
@@ -1,8 +1,7 @@
 use crate::{
 	ast::TypeKind,
-	builtins::{BuiltInType, Scalar, Vector},
+	builtins::{BuiltInType, Element, Scalar, Vector},
 };
-use num_traits::*;
 use std::{
 	fmt::{Debug, Formatter, Result},
 	ops::*,
@@ -10,17 +9,17 @@ use std::{
 
 #[repr(C)]
 #[derive(Clone, Copy)]
-pub struct Matrix<T: Scalar, const M: usize, const N: usize> {
+pub struct Matrix<T: Element, const M: usize, const N: usize> {
 	pub rows: [[T; N]; M],
 }
 
-impl<T: Scalar, const M: usize, const N: usize> Default for Matrix<T, M, N> {
+impl<T: Element, const M: usize, const N: usize> Default for Matrix<T, M, N> {
 	fn default() -> Self {
 		unsafe { std::mem::zeroed() }
 	}
 }
 
-impl<T: Scalar + Debug, const M: usize, const N: usize> Debug for Matrix<T, M, N> {
+impl<T: Element + Debug, const M: usize, const N: usize> Debug for Matrix<T, M, N> {
 	fn fmt(&self, formatter: &mut Formatter) -> Result {
 		formatter.write_str("Matrix [")?;
 		for r in self.rows.iter() {
@@ -32,16 +31,16 @@ impl<T: Scalar + Debug, const M: usize, const N: usize> Debug for Matrix<T, M, N
 	}
 }
 
-unsafe impl<T: 'static + Scalar, const M: usize, const N: usize> bytemuck::Pod for Matrix<T, M, N> {}
-unsafe impl<T: Scalar, const M: usize, const N: usize> bytemuck::Zeroable for Matrix<T, M, N> {}
+unsafe impl<T: Element, const M: usize, const N: usize> bytemuck::Pod for Matrix<T, M, N> {}
+unsafe impl<T: Element, const M: usize, const N: usize> bytemuck::Zeroable for Matrix<T, M, N> {}
 
-impl<T: Scalar, const M: usize, const N: usize> Matrix<T, M, N> {
+impl<T: Element, const M: usize, const N: usize> Matrix<T, M, N> {
 	pub fn new(arr: [[T; N]; M]) -> Self {
 		Self { rows: arr }
 	}
 
 	pub fn from_vecs(vecs: [Vector<T, N>; M]) -> Self {
-		let mut res = [[Zero::zero(); N]; M];
+		let mut res = [[T::default(); N]; M];
 		for (i, v) in vecs.iter().enumerate() {
 			res[i] = v.to_arr();
 		}
@@ -57,7 +56,7 @@ impl<T: Scalar, const M: usize, const N: usize> Matrix<T, M, N> {
 	}
 
 	pub fn get_col(self, col: usize) -> Vector<T, M> {
-		let mut result: [T; M] = [Zero::zero(); M];
+		let mut result: [T; M] = [T::default(); M];
 		for i in 0..M {
 			result[i] = self.rows[i][col];
 		}
@@ -89,7 +88,133 @@ impl<T: Scalar, const M: usize, const N: usize, const R: usize> Mul<Matrix<T, N,
 	}
 }
 
-impl<T: Scalar, const M: usize, const N: usize> PartialEq<Self> for Matrix<T, M, N> {
+// The storage is column major like in GLSL, so `rows` holds the GLSL columns and a `MatMxN` is a `Matrix<T, M, N>`.
+// The methods below take care of the translation, e.g. `mul_vec` is GLSL's `m * v`.
+impl<T: Scalar, const M: usize, const N: usize> Matrix<T, M, N> {
+	/// `self * v` in GLSL terms, where `v` has one component per column.
+	pub fn mul_vec(self, v: Vector<T, M>) -> Vector<T, N> {
+		let mut result: Vector<T, N> = Default::default();
+		for r in 0..N {
+			result.set_elem(r, self.get_col(r).dot(v));
+		}
+		result
+	}
+
+	/// `v * self` in GLSL terms, where `v` has one component per row.
+	pub fn vec_mul(self, v: Vector<T, N>) -> Vector<T, M> {
+		let mut result: Vector<T, M> = Default::default();
+		for c in 0..M {
+			result.set_elem(c, self.get_row(c).dot(v));
+		}
+		result
+	}
+
+	/// `self * other` in GLSL terms.
+	pub fn mul_mat<const C: usize>(self, other: Matrix<T, C, M>) -> Matrix<T, C, N> {
+		other * self
+	}
+}
+
+impl<const M: usize, const N: usize> Matrix<f32, M, N> {
+	/// A matrix with `x` on the diagonal and zeros everywhere else, like `mat3(x)` in GLSL.
+	pub fn diagonal(x: f32) -> Self {
+		let mut result: Self = Default::default();
+		for i in 0..M.min(N) {
+			result.rows[i][i] = x;
+		}
+		result
+	}
+
+	/// Converts to a matrix of another size like GLSL does, keeping the overlapping elements and taking the rest from
+	/// the identity matrix.
+	pub fn resize<const P: usize, const Q: usize>(self) -> Matrix<f32, P, Q> {
+		let mut result = Matrix::<f32, P, Q>::diagonal(1.0);
+		for i in 0..M.min(P) {
+			for j in 0..N.min(Q) {
+				result.rows[i][j] = self.rows[i][j];
+			}
+		}
+		result
+	}
+}
+
+impl<const N: usize> Matrix<f32, N, N> {
+	pub fn identity() -> Self {
+		let mut result: Self = Default::default();
+		for i in 0..N {
+			result.rows[i][i] = 1.0;
+		}
+		result
+	}
+
+	pub fn determinant(self) -> f32 {
+		let mut m = self.rows;
+		let mut det = 1.0;
+
+		// reduce to an upper triangular matrix, the determinant is the product of the diagonal
+		for c in 0..N {
+			let pivot = (c..N)
+				.max_by(|&a, &b| m[a][c].abs().partial_cmp(&m[b][c].abs()).unwrap_or(std::cmp::Ordering::Equal))
+				.unwrap();
+			if m[pivot][c] == 0.0 {
+				return 0.0;
+			}
+			if pivot != c {
+				m.swap(pivot, c);
+				det = -det;
+			}
+
+			det *= m[c][c];
+			let pivot_row = m[c];
+			for row in m.iter_mut().skip(c + 1) {
+				let factor = row[c] / pivot_row[c];
+				for (x, p) in row.iter_mut().zip(pivot_row.iter()).skip(c) {
+					*x -= factor * p;
+				}
+			}
+		}
+
+		det
+	}
+
+	/// Inverts the matrix with Gauss-Jordan elimination. Like in GLSL the result is undefined for singular matrices,
+	/// here every element is NaN.
+	pub fn inverse(self) -> Self {
+		let mut m = self.rows;
+		let mut inv = Self::identity().rows;
+
+		for c in 0..N {
+			let pivot = (c..N)
+				.max_by(|&a, &b| m[a][c].abs().partial_cmp(&m[b][c].abs()).unwrap_or(std::cmp::Ordering::Equal))
+				.unwrap();
+			if m[pivot][c] == 0.0 {
+				return Self::new([[f32::NAN; N]; N]);
+			}
+			m.swap(pivot, c);
+			inv.swap(pivot, c);
+
+			let scale = m[c][c];
+			for k in 0..N {
+				m[c][k] /= scale;
+				inv[c][k] /= scale;
+			}
+
+			for r in 0..N {
+				if r != c {
+					let factor = m[r][c];
+					for k in 0..N {
+						m[r][k] -= factor * m[c][k];
+						inv[r][k] -= factor * inv[c][k];
+					}
+				}
+			}
+		}
+
+		Self::new(inv)
+	}
+}
+
+impl<T: Element, const M: usize, const N: usize> PartialEq<Self> for Matrix<T, M, N> {
 	fn eq(&self, other: &Self) -> bool {
 		for i in 0..M {
 			for j in 0..N {
@@ -102,7 +227,7 @@ impl<T: Scalar, const M: usize, const N: usize> PartialEq<Self> for Matrix<T, M,
 	}
 }
 
-impl<T: Scalar, const M: usize, const N: usize> BuiltInType for Matrix<T, M, N> {
+impl<T: Element, const M: usize, const N: usize> BuiltInType for Matrix<T, M, N> {
 	fn stack_size() -> usize {
 		std::mem::size_of::<T>() * M * N
 	}
@@ -142,4 +267,20 @@ mod tests {
 
 		assert_eq!(lhs * rhs, result);
 	}
+
+	#[test]
+	fn inverse() {
+		let m = Matrix {
+			rows: [[2.0, 1.0, 0.0], [0.0, 1.0, 4.0], [1.0, 0.0, 3.0]],
+		};
+
+		let product = m * m.inverse();
+		for r in 0..3 {
+			for c in 0..3 {
+				let expected = if r == c { 1.0 } else { 0.0 };
+				assert!((product.rows[r][c] - expected).abs() < 1e-6);
+			}
+		}
+		assert!((m.determinant() - 10.0).abs() < 1e-6);
+	}
 }
@@ -6,9 +6,16 @@ pub mod vector;
 pub use vector::*;
 pub mod matrix;
 pub use matrix::*;
+pub mod texture;
+pub use texture::*;
 
-pub trait Scalar: Copy + Num + ToPrimitive + BuiltInType {}
-impl<T: Copy + Num + ToPrimitive + BuiltInType> Scalar for T {}
+/// Anything that can be stored in a vector or matrix.
+pub trait Element: Copy + PartialEq + Default + BuiltInType + 'static {}
+impl<T: Copy + PartialEq + Default + BuiltInType + 'static> Element for T {}
+
+/// Elements that support arithmetic.
+pub trait Scalar: Element + Num + ToPrimitive {}
+impl<T: Element + Num + ToPrimitive> Scalar for T {}
 
 pub trait BuiltInType {
 	fn stack_size() -> usize;
@@ -17,6 +24,37 @@ pub trait BuiltInType {
 
 pub type Float = f32;
 pub type Int = i32;
+pub type UInt = u32;
+
+/// A boolean as it is stored on the stack, `0` for false and `1` for true.
+#[repr(transparent)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Bool(pub u32);
+
+unsafe impl bytemuck::Zeroable for Bool {}
+unsafe impl bytemuck::Pod for Bool {}
+
+impl From<bool> for Bool {
+	fn from(b: bool) -> Bool {
+		Bool(b as u32)
+	}
+}
+
+impl From<Bool> for bool {
+	fn from(b: Bool) -> bool {
+		b.0 != 0
+	}
+}
+
+impl BuiltInType for Bool {
+	fn stack_size() -> usize {
+		std::mem::size_of::<u32>()
+	}
+
+	fn type_kind() -> TypeKind {
+		TypeKind::Bool
+	}
+}
 
 impl BuiltInType for f32 {
 	fn stack_size() -> usize {
@@ -38,6 +76,16 @@ impl BuiltInType for i32 {
 	}
 }
 
+impl BuiltInType for u32 {
+	fn stack_size() -> usize {
+		std::mem::size_of::<u32>()
+	}
+
+	fn type_kind() -> TypeKind {
+		TypeKind::U32
+	}
+}
+
 use crate::glsl::BuiltInCallableGLSL;
 
 pub trait BuiltInCallable: BuiltInCallableGLSL {
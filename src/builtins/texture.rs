@@ -0,0 +1,226 @@
+use crate::{
+	ast::TypeKind,
+	builtins::{BuiltInType, IVec2, Vec2, Vec4},
+};
+
+/// A `Sampler2D` as it is stored on the stack, the handle of a texture bound to the VM or `0` if none is bound.
+#[repr(transparent)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Sampler2D(pub u32);
+
+unsafe impl bytemuck::Zeroable for Sampler2D {}
+unsafe impl bytemuck::Pod for Sampler2D {}
+
+impl BuiltInType for Sampler2D {
+	fn stack_size() -> usize {
+		std::mem::size_of::<u32>()
+	}
+
+	fn type_kind() -> TypeKind {
+		TypeKind::Sampler2D
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Filter {
+	Nearest,
+	Linear,
+}
+
+/// How texture coordinates outside of `[0, 1]` are mapped to texels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Wrap {
+	Repeat,
+	MirroredRepeat,
+	ClampToEdge,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SamplerState {
+	/// The filter used within a mipmap level.
+	pub filter: Filter,
+	/// The filter used between mipmap levels, `None` to only ever sample the base level.
+	pub mipmap_filter: Option<Filter>,
+	pub wrap_s: Wrap,
+	pub wrap_t: Wrap,
+}
+
+impl Default for SamplerState {
+	fn default() -> Self {
+		SamplerState {
+			filter: Filter::Linear,
+			mipmap_filter: None,
+			wrap_s: Wrap::Repeat,
+			wrap_t: Wrap::Repeat,
+		}
+	}
+}
+
+/// A single mipmap level, with its texels in row-major order.
+#[derive(Debug, Clone)]
+pub struct Image {
+	pub width: usize,
+	pub height: usize,
+	pub texels: Vec<Vec4>,
+}
+
+impl Image {
+	fn texel(&self, x: usize, y: usize) -> Vec4 {
+		self.texels[y * self.width + x]
+	}
+
+	/// The next smaller mipmap level, every texel is the average of (up to) 2x2 texels of this level.
+	fn downsample(&self) -> Image {
+		let width = (self.width / 2).max(1);
+		let height = (self.height / 2).max(1);
+
+		let mut texels = Vec::with_capacity(width * height);
+		for y in 0..height {
+			for x in 0..width {
+				let x0 = (2 * x).min(self.width - 1);
+				let x1 = (2 * x + 1).min(self.width - 1);
+				let y0 = (2 * y).min(self.height - 1);
+				let y1 = (2 * y + 1).min(self.height - 1);
+
+				let sum = self.texel(x0, y0) + self.texel(x1, y0) + self.texel(x0, y1) + self.texel(x1, y1);
+				texels.push(sum * 0.25);
+			}
+		}
+
+		Image { width, height, texels }
+	}
+
+	fn sample(&self, uv: Vec2, sampler: &SamplerState) -> Vec4 {
+		let u = uv.get_elem(0) * self.width as f32;
+		let v = uv.get_elem(1) * self.height as f32;
+
+		let texel = |x: i32, y: i32| {
+			self.texel(wrap(x, self.width, sampler.wrap_s), wrap(y, self.height, sampler.wrap_t))
+		};
+
+		match sampler.filter {
+			Filter::Nearest => texel(u.floor() as i32, v.floor() as i32),
+			Filter::Linear => {
+				// texel centers are at half-integer coordinates
+				let (u, v) = (u - 0.5, v - 0.5);
+				let (x, y) = (u.floor() as i32, v.floor() as i32);
+				let (s, t) = (u - u.floor(), v - v.floor());
+
+				// huge and infinite coordinates saturate to the limits of `i32`
+				let (x1, y1) = (x.saturating_add(1), y.saturating_add(1));
+				let top = mix(texel(x, y), texel(x1, y), s);
+				let bottom = mix(texel(x, y1), texel(x1, y1), s);
+				mix(top, bottom, t)
+			}
+		}
+	}
+}
+
+/// A texture that can be bound to a `Sampler2D` uniform, see `VirtualMachine::bind_texture`.
+#[derive(Debug, Clone)]
+pub struct Texture {
+	/// The mipmap levels, starting with the base level.
+	pub levels: Vec<Image>,
+	pub sampler: SamplerState,
+}
+
+impl Texture {
+	/// A texture without mipmaps. The texels are in row-major order, starting with the texel at `uv = (0, 0)`.
+	pub fn new(width: usize, height: usize, texels: Vec<Vec4>) -> Self {
+		assert!(width > 0 && height > 0, "textures can't be empty");
+		assert_eq!(texels.len(), width * height, "expected {}x{} texels", width, height);
+
+		Texture {
+			levels: vec![Image { width, height, texels }],
+			sampler: SamplerState::default(),
+		}
+	}
+
+	pub fn with_sampler(mut self, sampler: SamplerState) -> Self {
+		self.sampler = sampler;
+		self
+	}
+
+	/// Generates all mipmap levels down to 1x1 from the base level.
+	pub fn with_mipmaps(mut self) -> Self {
+		self.levels.truncate(1);
+
+		loop {
+			let last = self.levels.last().unwrap();
+			if last.width == 1 && last.height == 1 {
+				break;
+			}
+
+			let next = last.downsample();
+			self.levels.push(next);
+		}
+
+		self
+	}
+
+	/// The size of a mipmap level, `(0, 0)` if the texture doesn't have that level.
+	pub fn size(&self, level: i32) -> IVec2 {
+		match self.level(level) {
+			Some(image) => IVec2::from_arr([image.width as i32, image.height as i32]),
+			None => IVec2::default(),
+		}
+	}
+
+	/// A single texel of a mipmap level without any filtering or wrapping, zero if it is out of range.
+	pub fn fetch(&self, x: i32, y: i32, level: i32) -> Vec4 {
+		match self.level(level) {
+			Some(image) if (0..image.width as i32).contains(&x) && (0..image.height as i32).contains(&y) => {
+				image.texel(x as usize, y as usize)
+			}
+			_ => Vec4::default(),
+		}
+	}
+
+	/// Samples the texture at `uv` from the mipmap level of detail `lod`, where level `n` is `lod = n`.
+	pub fn sample(&self, uv: Vec2, lod: f32) -> Vec4 {
+		let max_level = (self.levels.len() - 1) as f32;
+		let lod = lod.max(0.0).min(max_level);
+
+		match self.sampler.mipmap_filter {
+			None => self.levels[0].sample(uv, &self.sampler),
+			Some(Filter::Nearest) => self.levels[lod.round() as usize].sample(uv, &self.sampler),
+			Some(Filter::Linear) => {
+				let lower = lod.floor();
+				let upper = (lower + 1.0).min(max_level);
+
+				let a = self.levels[lower as usize].sample(uv, &self.sampler);
+				let b = self.levels[upper as usize].sample(uv, &self.sampler);
+				mix(a, b, lod - lower)
+			}
+		}
+	}
+
+	fn level(&self, level: i32) -> Option<&Image> {
+		if level < 0 {
+			return None;
+		}
+		self.levels.get(level as usize)
+	}
+}
+
+/// Maps the texel coordinate `coord` to `0..size`.
+fn wrap(coord: i32, size: usize, mode: Wrap) -> usize {
+	let size = size as i32;
+	let coord = match mode {
+		Wrap::Repeat => coord.rem_euclid(size),
+		Wrap::MirroredRepeat => {
+			let period = coord.rem_euclid(2 * size);
+			if period < size {
+				period
+			} else {
+				2 * size - 1 - period
+			}
+		}
+		Wrap::ClampToEdge => coord.max(0).min(size - 1),
+	};
+	coord as usize
+}
+
+fn mix(a: Vec4, b: Vec4, t: f32) -> Vec4 {
+	a * (1.0 - t) + b * t
+}
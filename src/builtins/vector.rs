@@ -1,6 +1,6 @@
 use crate::{
 	ast::TypeKind,
-	builtins::{BuiltInType, Matrix, Scalar},
+	builtins::{Bool, BuiltInType, Element, Matrix, Scalar},
 };
 use num_traits::*;
 use std::{
@@ -10,22 +10,22 @@ use std::{
 
 #[repr(C)]
 #[derive(Clone, Copy, PartialEq)]
-pub struct Vector<T: Scalar, const N: usize>(Matrix<T, 1, N>);
+pub struct Vector<T: Element, const N: usize>(Matrix<T, 1, N>);
 
-impl<T: Scalar, const N: usize> Default for Vector<T, N> {
+impl<T: Element, const N: usize> Default for Vector<T, N> {
 	fn default() -> Self {
 		unsafe { std::mem::zeroed() }
 	}
 }
 
-impl<T: Scalar + Debug, const N: usize> Debug for Vector<T, N> {
+impl<T: Element + Debug, const N: usize> Debug for Vector<T, N> {
 	fn fmt(&self, formatter: &mut Formatter) -> Result {
 		self.0.fmt(formatter)
 	}
 }
 
-unsafe impl<T: 'static + Scalar, const N: usize> bytemuck::Pod for Vector<T, N> {}
-unsafe impl<T: Scalar, const N: usize> bytemuck::Zeroable for Vector<T, N> {}
+unsafe impl<T: Element, const N: usize> bytemuck::Pod for Vector<T, N> {}
+unsafe impl<T: Element, const N: usize> bytemuck::Zeroable for Vector<T, N> {}
 
 impl<T: Scalar, const N: usize> Mul<T> for Vector<T, N> {
 	type Output = Self;
@@ -71,7 +71,7 @@ impl<T: Scalar, const N: usize> Div<T> for Vector<T, N> {
 	}
 }
 
-impl<T: Scalar, const N: usize> Vector<T, N> {
+impl<T: Element, const N: usize> Vector<T, N> {
 	pub fn from_arr(elems: [T; N]) -> Self {
 		Self(Matrix { rows: [elems] })
 	}
@@ -88,6 +88,34 @@ impl<T: Scalar, const N: usize> Vector<T, N> {
 		self.0.rows[0][n] = v;
 	}
 
+	pub fn from_fn(f: impl Fn(usize) -> T) -> Self {
+		let mut result = Self::default();
+		for i in 0..N {
+			result.set_elem(i, f(i));
+		}
+		result
+	}
+
+	/// Applies `f` to every component.
+	pub fn map<U: Element>(self, f: impl Fn(T) -> U) -> Vector<U, N> {
+		let mut result = Vector::default();
+		for i in 0..N {
+			result.set_elem(i, f(self.get_elem(i)));
+		}
+		result
+	}
+
+	/// Combines the components of both vectors pairwise.
+	pub fn zip_with<U: Element, R: Element>(self, other: Vector<U, N>, f: impl Fn(T, U) -> R) -> Vector<R, N> {
+		let mut result = Vector::default();
+		for i in 0..N {
+			result.set_elem(i, f(self.get_elem(i), other.get_elem(i)));
+		}
+		result
+	}
+}
+
+impl<T: Scalar, const N: usize> Vector<T, N> {
 	pub fn length_squared(self) -> T {
 		let mut sum = Zero::zero();
 		for i in 0..N {
@@ -116,7 +144,7 @@ impl<T: Scalar + From<f32>, const N: usize> Vector<T, N> {
 }
 
 //TODO: Add a macro to automate this
-impl<T: Scalar> Vector<T, 4> {
+impl<T: Element> Vector<T, 4> {
 	pub fn x(self) -> T {
 		self.get_elem(0)
 	}
@@ -138,7 +166,7 @@ impl<T: Scalar> Vector<T, 4> {
 	}
 }
 
-impl<T: Scalar> Vector<T, 3> {
+impl<T: Element> Vector<T, 3> {
 	pub fn x(self) -> T {
 		self.get_elem(0)
 	}
@@ -156,7 +184,7 @@ impl<T: Scalar> Vector<T, 3> {
 	}
 }
 
-impl<T: Scalar> Vector<T, 2> {
+impl<T: Element> Vector<T, 2> {
 	pub fn x(&self) -> T {
 		self.get_elem(0)
 	}
@@ -173,8 +201,17 @@ impl<T: Scalar> Vector<T, 2> {
 pub type Vec4 = Vector<f32, 4>;
 pub type Vec3 = Vector<f32, 3>;
 pub type Vec2 = Vector<f32, 2>;
-
-impl<T: Scalar, const N: usize> BuiltInType for Vector<T, N> {
+pub type IVec4 = Vector<i32, 4>;
+pub type IVec3 = Vector<i32, 3>;
+pub type IVec2 = Vector<i32, 2>;
+pub type UVec4 = Vector<u32, 4>;
+pub type UVec3 = Vector<u32, 3>;
+pub type UVec2 = Vector<u32, 2>;
+pub type BVec4 = Vector<Bool, 4>;
+pub type BVec3 = Vector<Bool, 3>;
+pub type BVec2 = Vector<Bool, 2>;
+
+impl<T: Element, const N: usize> BuiltInType for Vector<T, N> {
 	fn stack_size() -> usize {
 		std::mem::size_of::<T>() * N
 	}
@@ -0,0 +1,112 @@
+use crate::{ast::*, compiler::resolve_types::TypeError, diagnostic::Diagnostic};
+
+/// A problem that doesn't stop the program from compiling.
+#[derive(Clone, Debug)]
+pub enum Warning {
+	/// The statement at the first span never runs, because the statement at the second span always jumps away.
+	UnreachableCode(Spanned<()>, Spanned<()>),
+}
+
+impl From<Warning> for Diagnostic {
+	fn from(w: Warning) -> Diagnostic {
+		match w {
+			Warning::UnreachableCode(span, jump) => Diagnostic::warning("unreachable statement")
+				.with_code("W0301")
+				.with_primary(span, "unreachable statement")
+				.with_secondary(jump, "any code following this is unreachable"),
+		}
+	}
+}
+
+/// Whether control can reach the end of the statements, instead of always leaving them through a `return`, `break` or
+/// `continue`.
+pub fn completes(statements: &[Statement]) -> bool {
+	statements.iter().all(statement_completes)
+}
+
+fn statement_completes(statement: &Statement) -> bool {
+	match statement {
+		Statement::Return(_, _) | Statement::Break(_) | Statement::Continue(_) => false,
+		Statement::Conditional(c) => {
+			let mut branch = Some(c);
+			while let Some(b) = branch {
+				if completes(&b.body) {
+					return true;
+				}
+				if b.cond.is_none() {
+					// every branch, including the `else`, jumps away
+					return false;
+				}
+				branch = b.alternate.as_deref();
+			}
+
+			// without an `else`, none of the branches may run
+			true
+		}
+		Statement::While(cond, body) => !is_true(cond) || breaks(body),
+		// a `for` loop can always end, or never run at all
+		_ => true,
+	}
+}
+
+fn is_true(expr: &Expr) -> bool {
+	match expr {
+		Expr::Literal(l) => matches!(l.item, Literal::BoolLiteral(true)),
+		Expr::Grouped(e) => is_true(e),
+		_ => false,
+	}
+}
+
+/// Whether the statements contain a `break` out of the loop they are the body of.
+fn breaks(statements: &[Statement]) -> bool {
+	statements.iter().any(|s| match s {
+		Statement::Break(_) => true,
+		Statement::Conditional(c) => {
+			let mut branch = Some(c);
+			while let Some(b) = branch {
+				if breaks(&b.body) {
+					return true;
+				}
+				branch = b.alternate.as_deref();
+			}
+			false
+		}
+		// a `break` in a nested loop leaves only that loop
+		_ => false,
+	})
+}
+
+/// Warns about statements that follow a statement which always jumps away, once per block.
+fn unreachable_code(statements: &[Statement], warnings: &mut Vec<Warning>) {
+	for (i, statement) in statements.iter().enumerate() {
+		match statement {
+			Statement::Conditional(c) => {
+				let mut branch = Some(c);
+				while let Some(b) = branch {
+					unreachable_code(&b.body, warnings);
+					branch = b.alternate.as_deref();
+				}
+			}
+			Statement::Loop(l) => unreachable_code(&l.body, warnings),
+			Statement::While(_, body) => unreachable_code(body, warnings),
+			_ => {}
+		}
+
+		if !statement_completes(statement) {
+			if let Some(next) = statements.get(i + 1) {
+				warnings.push(Warning::UnreachableCode(next.span(), statement.span()));
+			}
+			return;
+		}
+	}
+}
+
+/// Checks that a function returns a value on every path, unless it returns `Void`, and warns about unreachable code.
+pub fn check_function(func: &FunctionDeclaration, errors: &mut Vec<TypeError>, warnings: &mut Vec<Warning>) {
+	unreachable_code(&func.statements, warnings);
+
+	let returns_value = !matches!(func.ret_type.item, TypeKind::Void | TypeKind::Error);
+	if returns_value && completes(&func.statements) {
+		errors.push(TypeError::MissingReturn(func.ident.clone(), func.ret_type.clone()));
+	}
+}
@@ -1,19 +1,153 @@
-use crate::{ast::*, vm::*};
+use crate::{ast::*, diagnostic::Diagnostic, vm::*};
 
+pub mod control_flow;
 pub mod program_data;
 pub mod resolve_types;
 
-use program_data::{FuncMeta, ProgramData, SymbolMeta};
+use program_data::{signature, FuncMeta, LoopLabels, ProgramData, SymbolMeta};
+use resolve_types::TypeError;
+use std::{error, fmt};
+
+#[derive(Clone, Debug)]
+pub enum CompileError {
+	TypeErrors(Vec<TypeError>),
+	RedeclaredGlobal(Spanned<Ident>),
+	GlobalLoopVariable(Spanned<Ident>),
+	UnknownSymbol(Spanned<Ident>),
+	UnknownFunction(Spanned<Ident>, Vec<TypeKind>),
+	/// An invariant that type checking should have guaranteed did not hold.
+	Internal(Spanned<()>, String),
+}
+
+impl CompileError {
+	fn internal(span: Spanned<()>, message: impl Into<String>) -> Self {
+		CompileError::Internal(span, message.into())
+	}
+
+	pub fn diagnostics(&self) -> Vec<Diagnostic> {
+		match self {
+			CompileError::TypeErrors(errors) => errors.iter().cloned().map(Diagnostic::from).collect(),
+			CompileError::RedeclaredGlobal(i) => vec![Diagnostic::error(format!(
+				"cannot declare a local variable named `{}`",
+				i.item
+			))
+			.with_code("E0401")
+			.with_primary(i.just_span(), "already declared as a global")],
+			CompileError::GlobalLoopVariable(i) => vec![Diagnostic::error(format!(
+				"cannot use the global `{}` as a loop variable",
+				i.item
+			))
+			.with_code("E0402")
+			.with_primary(i.just_span(), "already declared as a global")],
+			CompileError::UnknownSymbol(i) => {
+				vec![TypeError::UnknownSymbol(i.clone()).into()]
+			}
+			CompileError::UnknownFunction(i, args) => {
+				vec![TypeError::UnknownFunction(i.clone(), args.clone()).into()]
+			}
+			CompileError::Internal(span, message) => vec![Diagnostic::error(format!(
+				"internal compiler error: {}",
+				message
+			))
+			.with_code("E0499")
+			.with_primary_span(*span)
+			.with_note("this is a bug in the compiler")],
+		}
+	}
+}
+
+impl fmt::Display for CompileError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let diagnostics = self.diagnostics();
+		for (i, d) in diagnostics.iter().enumerate() {
+			if i > 0 {
+				writeln!(f)?;
+			}
+			write!(f, "{}", d)?;
+		}
+		Ok(())
+	}
+}
+
+impl error::Error for CompileError {}
 
-pub fn compile(mut ast: Program) -> VMProgram {
+impl From<Vec<TypeError>> for CompileError {
+	fn from(errors: Vec<TypeError>) -> CompileError {
+		CompileError::TypeErrors(errors)
+	}
+}
+
+type CompileResult<T> = Result<T, CompileError>;
+
+pub fn compile(mut ast: Program) -> CompileResult<VMProgram> {
 	let mut program_data = ProgramData::new();
 
-	resolve_types::resolve(&mut ast, &mut program_data).unwrap();
+	resolve_types::resolve(&mut ast, &mut program_data)?;
 
 	codegen(ast, program_data)
 }
 
-pub fn codegen(ast: Program, data: ProgramData) -> VMProgram {
+fn typekind_of(expr: &Expr) -> CompileResult<TypeKind> {
+	expr.typekind()
+		.ok_or_else(|| CompileError::internal(expr.span(), "expression was not typed"))
+}
+
+/// The length of the array passed to a call of `length`, which is not a builtin since it works on any array.
+pub fn array_length(func: &FuncCall) -> Option<usize> {
+	match (func.0.raw.as_str(), &func.1[..]) {
+		("length", [array]) => match array.typekind() {
+			Some(TypeKind::Array(_, len)) => Some(len),
+			_ => None,
+		},
+		_ => None,
+	}
+}
+
+/// Whether the call compares two structs or arrays with `==` or `!=`, which is done element by element instead of by
+/// a builtin.
+pub fn is_aggregate_comparison(func: &FuncCall) -> bool {
+	match (func.0.raw.as_str(), &func.1[..]) {
+		("__op_binary_equality", [a, b]) | ("__op_binary_not_equal", [a, b]) => match (a.typekind(), b.typekind()) {
+			(Some(a @ TypeKind::Struct(_)), Some(b)) | (Some(a @ TypeKind::Array(_, _)), Some(b)) => a == b,
+			_ => false,
+		},
+		_ => false,
+	}
+}
+
+/// The scalars, vectors and matrix columns a value of the type consists of, with their byte offsets.
+fn comparable_parts(tk: &TypeKind, offset: usize, parts: &mut Vec<(usize, TypeKind)>) {
+	match tk {
+		TypeKind::Struct(s) => {
+			let mut offset = offset;
+			for (_, member) in s.borrow().members.iter() {
+				comparable_parts(member, offset, parts);
+				offset += member.size();
+			}
+		}
+		TypeKind::Array(element, n) => {
+			for i in 0..*n {
+				comparable_parts(element, offset + i * element.size(), parts);
+			}
+		}
+		TypeKind::Matrix(element, m, n) => {
+			let column = TypeKind::Vector(element.clone(), *n);
+			for i in 0..*m {
+				parts.push((offset + i * column.size(), column.clone()));
+			}
+		}
+		TypeKind::Error => {}
+		tk => parts.push((offset, tk.clone())),
+	}
+}
+
+fn get_builtin_fn(span: Spanned<()>, id: &str, arg_types: &[TypeKind]) -> CompileResult<usize> {
+	crate::builtins::get_builtin_fn(id, arg_types)
+		.map(|(i, _)| i)
+		.ok_or_else(|| CompileError::internal(span, format!("missing builtin `{}` {:?}", id, arg_types)))
+}
+
+pub fn codegen(ast: Program, data: ProgramData) -> CompileResult<VMProgram> {
 	let mut program = VMProgram::new();
 	program.data = data;
 
@@ -34,8 +168,15 @@ pub fn codegen(ast: Program, data: ProgramData) -> VMProgram {
 	}
 
 	for f in ast.functions.iter() {
+		let param_types = f.params.iter().map(|(tk, _)| tk.item.clone()).collect::<Vec<_>>();
+		let key = signature(&f.ident.item, &param_types);
+
 		let mut fnc = {
-			let mut fnc = program.data.functions.get_mut(f.ident.item.as_str()).unwrap();
+			let fnc = program
+				.data
+				.functions
+				.get_mut(&key)
+				.ok_or_else(|| CompileError::internal(f.ident.just_span(), "function was not resolved"))?;
 			fnc.address = Some(program.code.len());
 
 			fnc.stack_offset = fnc.param_types.iter().map(|t| t.size()).sum();
@@ -43,69 +184,293 @@ pub fn codegen(ast: Program, data: ProgramData) -> VMProgram {
 		};
 
 		for s in f.statements.iter() {
-			generate_statement(&mut program, &ast, &mut fnc, s);
+			generate_statement(&mut program, &ast, &mut fnc, s)?;
+		}
+
+		// only `Void` functions may reach the end of their body, they return nothing
+		if control_flow::completes(&f.statements) {
+			program.code.push(MemoryCell::with_data(OpCode::Ret, 0));
 		}
-		*program.data.functions.get_mut(f.ident.item.as_str()).unwrap() = fnc;
+		*program.data.functions.get_mut(&key).unwrap() = fnc;
 	}
 
 	program.data.static_section_size = static_section;
-	program
+	Ok(program)
+}
+
+/// Allocates stack space for a local variable at the top of the current stack frame.
+fn declare_local(fnc: &mut FuncMeta, i: &Spanned<Ident>, type_kind: TypeKind, is_mutable: bool) {
+	let size = type_kind.size();
+	let symbol = SymbolMeta {
+		type_kind,
+		is_static: false,
+		is_mutable,
+		stack_offset: Some(fnc.stack_offset),
+	};
+
+	fnc.declare(&i.item, symbol);
+	fnc.stack_offset += size;
+}
+
+/// Ends the innermost scope, freeing the stack space of the variables declared in it.
+fn pop_scope(program: &mut VMProgram, fnc: &mut FuncMeta) {
+	let size = fnc.pop_scope().values().map(|s| s.type_kind.size()).sum::<usize>();
+
+	if size > 0 {
+		program.code.push(MemoryCell::with_data(OpCode::Discard, size as u16));
+		program.code.push(MemoryCell::raw(0));
+		fnc.stack_offset -= size;
+	}
+}
+
+fn generate_block(program: &mut VMProgram, ast: &Program, fnc: &mut FuncMeta, body: &[Statement]) -> CompileResult<()> {
+	fnc.push_scope();
+	for s in body.iter() {
+		generate_statement(program, ast, fnc, s)?;
+	}
+	pop_scope(program, fnc);
+
+	Ok(())
+}
+
+/// Generates the body of a loop, leaving the `break` and `continue` jumps in it in `fnc.loops` to be patched.
+fn generate_loop_body(
+	program: &mut VMProgram,
+	ast: &Program,
+	fnc: &mut FuncMeta,
+	body: &[Statement],
+) -> CompileResult<()> {
+	fnc.loops.push(LoopLabels {
+		depth: fnc.scopes.len(),
+		..LoopLabels::default()
+	});
+	generate_block(program, ast, fnc, body)
+}
+
+fn patch_jumps(program: &mut VMProgram, jumps: &[usize], target: usize) {
+	for &jump in jumps {
+		program.code[jump] = MemoryCell::with_data(OpCode::Jmp, target as u16);
+	}
+}
+
+/// The indices of the 4 byte words that the field or swizzle `f` selects from a value of type `base`.
+fn field_words(base: &TypeKind, f: &Spanned<Ident>, t: &Option<TypeKind>, so: &Option<usize>) -> CompileResult<Vec<usize>> {
+	let ice = || CompileError::internal(f.just_span(), "field access was not resolved");
+
+	match base {
+		TypeKind::Struct(_) => {
+			let from = so.ok_or_else(ice)? / 4;
+			let len = t.as_ref().ok_or_else(ice)?.size() / 4;
+
+			Ok((from..from + len).collect())
+		}
+		TypeKind::Vector(_, n) => resolve_types::swizzle_components(f, *n).ok_or_else(ice),
+		_ => Err(ice()),
+	}
+}
+
+/// Where the value of a place expression, i.e. a variable or a part of one, is stored.
+enum Place {
+	/// The offsets of its words, in the static section if the flag is set or in the current stack frame otherwise.
+	Fixed(bool, Vec<usize>),
+	/// The offsets of its words relative to an absolute address, which has been pushed onto the stack.
+	Dynamic(Vec<usize>),
+}
+
+impl Place {
+	fn words(&self) -> &[usize] {
+		match self {
+			Place::Fixed(_, words) => words,
+			Place::Dynamic(words) => words,
+		}
+	}
+
+	/// Narrows the place to the words at the given indices.
+	fn select(self, indices: impl IntoIterator<Item = usize>) -> Place {
+		let selected = indices.into_iter().map(|i| self.words()[i]).collect();
+
+		match self {
+			Place::Fixed(is_static, _) => Place::Fixed(is_static, selected),
+			Place::Dynamic(_) => Place::Dynamic(selected),
+		}
+	}
+}
+
+/// Resolves a place expression to where it is stored, emitting the code computing its address for dynamic array
+/// indices. Returns `None` without emitting any code for expressions that are not stored anywhere.
+fn generate_place(program: &mut VMProgram, ast: &Program, fnc: &FuncMeta, expr: &Expr) -> CompileResult<Option<Place>> {
+	match expr {
+		Expr::Symbol(s) => {
+			let symbol = fnc
+				.lookup(s.raw.as_str())
+				.or_else(|| program.data.global_symbols.get(s.raw.as_str()))
+				.ok_or_else(|| CompileError::UnknownSymbol(s.raw.clone()))?;
+			let offset = symbol
+				.stack_offset
+				.ok_or_else(|| CompileError::internal(s.raw.just_span(), "symbol has no storage"))?;
+
+			let words = (0..symbol.type_kind.size() / 4).map(|i| offset + i * 4).collect();
+			Ok(Some(Place::Fixed(symbol.is_static, words)))
+		}
+		Expr::FieldAccess(e, f, t, so) => match generate_place(program, ast, fnc, e)? {
+			Some(place) => Ok(Some(place.select(field_words(&typekind_of(e)?, f, t, so)?))),
+			None => Ok(None),
+		},
+		Expr::Index(e, index, brackets, _) => {
+			let place = match generate_place(program, ast, fnc, e)? {
+				Some(place) => place,
+				None => return Ok(None),
+			};
+			let (element_size, len) = match typekind_of(e)? {
+				TypeKind::Array(element, len) => (element.size(), len),
+				_ => return Err(CompileError::internal(*brackets, "indexed value is not an array")),
+			};
+			let element_words = element_size / 4;
+
+			if let Some(i) = resolve_types::constant_index(index) {
+				let from = i as usize * element_words;
+				return Ok(Some(place.select(from..from + element_words)));
+			}
+
+			// arrays are always stored contiguously, so their address is the one of their first word
+			match place {
+				Place::Fixed(true, words) => {
+					program.code.push(MemoryCell::plain_inst(OpCode::Const4));
+					program.code.push(MemoryCell::raw(words[0] as u32));
+				}
+				Place::Fixed(false, words) => {
+					program
+						.code
+						.push(MemoryCell::with_data(OpCode::LocalAddr, words[0] as u16));
+				}
+				Place::Dynamic(words) if words[0] != 0 => {
+					program.code.push(MemoryCell::plain_inst(OpCode::Const4));
+					program.code.push(MemoryCell::raw(words[0] as u32));
+					let add = get_builtin_fn(*brackets, "__op_binary_add", &[TypeKind::U32, TypeKind::U32])?;
+					program
+						.code
+						.push(MemoryCell::with_data(OpCode::CallBuiltIn, add as u16));
+				}
+				Place::Dynamic(_) => {}
+			}
+
+			generate_expr(program, ast, fnc, index)?;
+			program.code.push(MemoryCell::with_data(OpCode::Index, len as u16));
+			program.code.push(MemoryCell::raw(element_size as u32));
+
+			Ok(Some(Place::Dynamic((0..element_words).map(|i| i * 4).collect())))
+		}
+		_ => Ok(None),
+	}
+}
+
+fn load_place(program: &mut VMProgram, place: Place) {
+	match place {
+		Place::Fixed(is_static, words) => {
+			let instruction = match is_static {
+				true => OpCode::Load4Global,
+				false => OpCode::Load4,
+			};
+
+			for offset in words {
+				program.code.push(MemoryCell::with_data(instruction, offset as u16));
+			}
+		}
+		Place::Dynamic(words) => {
+			// keep a copy of the address on top of the stack for every load, then drop the original
+			for (i, offset) in words.iter().enumerate() {
+				program.code.push(MemoryCell::with_data(OpCode::Peek4, (i * 4) as u16));
+				program.code.push(MemoryCell::with_data(OpCode::Load4Addr, *offset as u16));
+			}
+			program.code.push(MemoryCell::with_data(OpCode::Discard, 4));
+			program.code.push(MemoryCell::raw(words.len() as u32 * 4));
+		}
+	}
+}
+
+/// Pops the value on top of the stack (below the address for dynamic places) into the place.
+fn store_place(program: &mut VMProgram, place: Place) {
+	match place {
+		Place::Fixed(is_static, words) => {
+			let instruction = match is_static {
+				true => OpCode::Mov4Global,
+				false => OpCode::Mov4,
+			};
+
+			for offset in words.iter().rev() {
+				program.code.push(MemoryCell::with_data(instruction, *offset as u16));
+			}
+		}
+		Place::Dynamic(words) => {
+			for offset in words.iter().rev() {
+				program.code.push(MemoryCell::with_data(OpCode::Mov4Addr, *offset as u16));
+			}
+			program.code.push(MemoryCell::with_data(OpCode::Discard, 4));
+			program.code.push(MemoryCell::raw(0));
+		}
+	}
 }
 
-pub fn generate_statement(program: &mut VMProgram, ast: &Program, fnc: &mut FuncMeta, statement: &Statement) {
+pub fn generate_statement(
+	program: &mut VMProgram,
+	ast: &Program,
+	fnc: &mut FuncMeta,
+	statement: &Statement,
+) -> CompileResult<()> {
 	match statement {
-		Statement::VariableDeclaration(_, i, expr) => {
-			generate_expr(program, &ast, fnc, &expr);
+		Statement::VariableDeclaration(is_mut, i, expr) => {
+			generate_expr(program, ast, fnc, expr)?;
 
 			if let Some(_) = program.data.global_symbols.get(&i.item) {
-				panic!("Don't redeclare a global");
+				return Err(CompileError::RedeclaredGlobal(i.clone()));
 			} else {
-				fnc.symbols.get_mut(i.item.as_str()).unwrap().stack_offset = Some(fnc.stack_offset);
-				fnc.stack_offset += expr.typekind().unwrap().size();
+				declare_local(fnc, i, typekind_of(expr)?, *is_mut);
 			}
 
 			program
 				.code
 				.push(MemoryCell::with_data(OpCode::StmtMarker, i.from.line as u16));
 		}
-		Statement::Assignment(i, expr) => {
-			generate_expr(program, &ast, fnc, &expr);
+		Statement::Assignment(place, expr) => {
+			generate_expr(program, ast, fnc, expr)?;
 
-			if let Some(o) = program.data.global_symbols.get(&i.item) {
-				let size = o.type_kind.size() / 4;
-
-				for i in 1..=size {
-					program.code.push(MemoryCell::with_data(
-						OpCode::Mov4Global,
-						(o.stack_offset.unwrap() + ((size - i) * 4)) as u16,
-					));
-				}
-			} else if let Some(o) = fnc.symbols.get(&i.item) {
-				let size = o.type_kind.size() / 4;
-
-				for i in 1..=size {
-					program.code.push(MemoryCell::with_data(
-						OpCode::Mov4,
-						(o.stack_offset.unwrap() + ((size - i) * 4)) as u16,
-					));
-				}
-			} else {
-				panic!("[ICE: Assignment to unknown symbol after typechecking]");
-			}
+			let target = generate_place(program, ast, fnc, place)?
+				.ok_or_else(|| CompileError::internal(place.span(), "invalid assignment target"))?;
+			store_place(program, target);
 
 			program
 				.code
-				.push(MemoryCell::with_data(OpCode::StmtMarker, i.from.line as u16));
+				.push(MemoryCell::with_data(OpCode::StmtMarker, place.span().from.line as u16));
 		}
-		Statement::Return(span, expr) => {
-			generate_expr(program, &ast, fnc, &expr);
+		Statement::Return(span, Some(expr)) => {
+			generate_expr(program, ast, fnc, expr)?;
 
 			program
 				.code
 				.push(MemoryCell::with_data(OpCode::StmtMarker, span.from.line as u16));
 			program
 				.code
-				.push(MemoryCell::with_data(OpCode::Ret, expr.expect_typekind().size() as u16));
+				.push(MemoryCell::with_data(OpCode::Ret, typekind_of(expr)?.size() as u16));
+		}
+		Statement::Return(span, None) => {
+			program
+				.code
+				.push(MemoryCell::with_data(OpCode::StmtMarker, span.from.line as u16));
+			program.code.push(MemoryCell::with_data(OpCode::Ret, 0));
+		}
+		Statement::Expression(expr) => {
+			generate_expr(program, ast, fnc, expr)?;
+
+			// drop the unused value
+			let size = typekind_of(expr)?.size();
+			if size > 0 {
+				program.code.push(MemoryCell::with_data(OpCode::Discard, size as u16));
+				program.code.push(MemoryCell::raw(0));
+			}
+
+			program
+				.code
+				.push(MemoryCell::with_data(OpCode::StmtMarker, expr.span().from.line as u16));
 		}
 		Statement::Conditional(cond) => {
 			fn generate_conditional_branch(
@@ -113,112 +478,267 @@ pub fn generate_statement(program: &mut VMProgram, ast: &Program, fnc: &mut Func
 				ast: &Program,
 				fnc: &mut FuncMeta,
 				cond: &Conditional,
-			) {
+			) -> CompileResult<()> {
 				if let Some(c) = &cond.cond {
-					generate_expr(program, ast, fnc, &c);
+					generate_expr(program, ast, fnc, c)?;
 					let label = program.code.len();
 					program.code.push(MemoryCell::with_data(OpCode::JmpZero, 0));
-					for s in cond.body.iter() {
-						generate_statement(program, ast, fnc, s);
+					generate_block(program, ast, fnc, &cond.body)?;
+
+					if let Some(alt) = &cond.alternate {
+						// skip the alternate branches after executing the body
+						let end_label = program.code.len();
+						program.code.push(MemoryCell::with_data(OpCode::Jmp, 0));
+						program.code[label] = MemoryCell::with_data(OpCode::JmpZero, program.code.len() as u16);
+
+						generate_conditional_branch(program, ast, fnc, alt)?;
+						program.code[end_label] = MemoryCell::with_data(OpCode::Jmp, program.code.len() as u16);
+					} else {
+						program.code[label] = MemoryCell::with_data(OpCode::JmpZero, program.code.len() as u16);
 					}
-					program.code[label] = MemoryCell::with_data(OpCode::JmpZero, program.code.len() as u16);
 				} else {
-					for s in cond.body.iter() {
-						generate_statement(program, ast, fnc, s);
-					}
+					generate_block(program, ast, fnc, &cond.body)?;
 				}
 
-				if let Some(c) = &cond.alternate {
-					generate_conditional_branch(program, ast, fnc, &c);
-				}
+				Ok(())
 			}
 
-			generate_conditional_branch(program, ast, fnc, &cond);
+			generate_conditional_branch(program, ast, fnc, cond)?;
 		}
-		Statement::Loop(ident, from, to, body) => {
-			generate_expr(program, &ast, fnc, &from);
+		Statement::Loop(l) => {
+			let ident = &l.ident;
+			generate_expr(program, ast, fnc, &l.from)?;
 
 			if let Some(_) = program.data.global_symbols.get(&ident.item) {
-				panic!("Don't use a global in a loop");
-			} else {
-				// loop index var
-				fnc.symbols.get_mut(ident.item.as_str()).unwrap().stack_offset = Some(fnc.stack_offset);
-				let iter_offset = fnc.stack_offset;
-				fnc.stack_offset += from.typekind().unwrap().size();
+				return Err(CompileError::GlobalLoopVariable(ident.clone()));
+			}
 
-				// condition
-				let cond = program.code.len();
-				program
-					.code
-					.push(MemoryCell::with_data(OpCode::Load4, iter_offset as u16));
-				generate_expr(program, ast, fnc, to);
-				let cmp_fn = crate::builtins::get_builtin_fn("__op_binary_less", &[TypeKind::I32, TypeKind::I32])
-					.unwrap()
-					.0;
-				program
-					.code
-					.push(MemoryCell::with_data(OpCode::CallBuiltIn, cmp_fn as u16));
+			// loop index var, in a scope around the body
+			let counter = typekind_of(&l.from)?;
+			let iter_offset = fnc.stack_offset;
+			fnc.push_scope();
+			declare_local(fnc, ident, counter.clone(), false);
 
-				program
-					.code
-					.push(MemoryCell::with_data(OpCode::StmtMarker, ident.from.line as u16));
+			// condition, counting down if the step is negative
+			let step = l.step.as_ref().and_then(resolve_types::constant_step).unwrap_or(1.0);
+			let cmp = if step < 0.0 { "__op_binary_greater" } else { "__op_binary_less" };
 
-				let jmp = program.code.len();
-				program.code.push(MemoryCell::with_data(OpCode::JmpZero, 0));
+			let cond = program.code.len();
+			program
+				.code
+				.push(MemoryCell::with_data(OpCode::Load4, iter_offset as u16));
+			generate_expr(program, ast, fnc, &l.to)?;
+			let cmp_fn = get_builtin_fn(ident.just_span(), cmp, &[counter.clone(), counter.clone()])?;
+			program
+				.code
+				.push(MemoryCell::with_data(OpCode::CallBuiltIn, cmp_fn as u16));
 
-				// body
-				for s in body.iter() {
-					generate_statement(program, ast, fnc, s);
+			program
+				.code
+				.push(MemoryCell::with_data(OpCode::StmtMarker, ident.from.line as u16));
+
+			let jmp = program.code.len();
+			program.code.push(MemoryCell::with_data(OpCode::JmpZero, 0));
+
+			// body
+			generate_loop_body(program, ast, fnc, &l.body)?;
+			let labels = fnc.loops.pop().unwrap();
+
+			// incr loop index, where `continue` jumps to
+			patch_jumps(program, &labels.continues, program.code.len());
+			match &l.step {
+				Some(step) => generate_expr(program, ast, fnc, step)?,
+				None => {
+					let one = match counter {
+						TypeKind::F32 => 1.0f32.to_bits(),
+						_ => 1,
+					};
+					program.code.push(MemoryCell::plain_inst(OpCode::Const4));
+					program.code.push(MemoryCell::raw(one));
 				}
+			}
+			program
+				.code
+				.push(MemoryCell::with_data(OpCode::Load4, iter_offset as u16));
+			let incr_fn = get_builtin_fn(ident.just_span(), "__op_binary_add", &[counter.clone(), counter])?;
+			program
+				.code
+				.push(MemoryCell::with_data(OpCode::CallBuiltIn, incr_fn as u16));
+			program
+				.code
+				.push(MemoryCell::with_data(OpCode::Mov4, iter_offset as u16));
 
-				// incr loop index
-				program.code.push(MemoryCell::plain_inst(OpCode::Const4));
-				program.code.push(MemoryCell::raw(1));
-				program
-					.code
-					.push(MemoryCell::with_data(OpCode::Load4, iter_offset as u16));
-				let incr_fn = crate::builtins::get_builtin_fn("__op_binary_add", &[TypeKind::I32, TypeKind::I32])
-					.unwrap()
-					.0;
-				program
-					.code
-					.push(MemoryCell::with_data(OpCode::CallBuiltIn, incr_fn as u16));
-				program
-					.code
-					.push(MemoryCell::with_data(OpCode::Mov4, iter_offset as u16));
+			// jump to condition
+			program.code.push(MemoryCell::with_data(OpCode::Jmp, cond as u16));
+
+			// end label, `break` jumps here before the index var is freed
+			program.code[jmp] = MemoryCell::with_data(OpCode::JmpZero, program.code.len() as u16);
+			patch_jumps(program, &labels.breaks, program.code.len());
+			pop_scope(program, fnc);
+		}
+		Statement::While(cond, body) => {
+			let start = program.code.len();
+			generate_expr(program, ast, fnc, cond)?;
+			program
+				.code
+				.push(MemoryCell::with_data(OpCode::StmtMarker, cond.span().from.line as u16));
 
-				// jump to condition
-				program.code.push(MemoryCell::with_data(OpCode::Jmp, cond as u16));
+			let jmp = program.code.len();
+			program.code.push(MemoryCell::with_data(OpCode::JmpZero, 0));
 
-				// end label
-				program.code[jmp] = MemoryCell::with_data(OpCode::JmpZero, program.code.len() as u16);
+			generate_loop_body(program, ast, fnc, body)?;
+			let labels = fnc.loops.pop().unwrap();
+			program.code.push(MemoryCell::with_data(OpCode::Jmp, start as u16));
+
+			program.code[jmp] = MemoryCell::with_data(OpCode::JmpZero, program.code.len() as u16);
+			patch_jumps(program, &labels.continues, start);
+			patch_jumps(program, &labels.breaks, program.code.len());
+		}
+		Statement::Break(span) | Statement::Continue(span) => {
+			let labels = fnc
+				.loops
+				.last()
+				.ok_or_else(|| CompileError::internal(*span, "jump outside of a loop"))?;
+
+			// free the variables of the blocks inside the loop, the code after the jump keeps using them
+			let size = fnc.scopes[labels.depth..]
+				.iter()
+				.flat_map(|scope| scope.values())
+				.map(|s| s.type_kind.size())
+				.sum::<usize>();
+			if size > 0 {
+				program.code.push(MemoryCell::with_data(OpCode::Discard, size as u16));
+				program.code.push(MemoryCell::raw(0));
+			}
+
+			program
+				.code
+				.push(MemoryCell::with_data(OpCode::StmtMarker, span.from.line as u16));
+			let label = program.code.len();
+			program.code.push(MemoryCell::with_data(OpCode::Jmp, 0));
+
+			let labels = fnc.loops.last_mut().unwrap();
+			match statement {
+				Statement::Break(_) => labels.breaks.push(label),
+				_ => labels.continues.push(label),
 			}
 		}
 	};
+
+	Ok(())
 }
 
-pub fn generate_expr(program: &mut VMProgram, ast: &Program, fnc: &FuncMeta, expr: &Expr) {
+fn is_short_circuit(ident: &str) -> bool {
+	ident == "__op_binary_and" || ident == "__op_binary_or"
+}
+
+pub fn generate_expr(program: &mut VMProgram, ast: &Program, fnc: &FuncMeta, expr: &Expr) -> CompileResult<()> {
 	match expr {
+		Expr::FuncCall((id, args)) if is_short_circuit(id.raw.as_str()) => {
+			// `a && b` => if a { b } else { false }, `a || b` => if a { true } else { b }
+			let (skip, skipped_value) = match id.raw.as_str() {
+				"__op_binary_and" => (OpCode::JmpZero, 0),
+				_ => (OpCode::JmpNotZero, 1),
+			};
+
+			generate_expr(program, ast, fnc, &args[0])?;
+			let skip_label = program.code.len();
+			program.code.push(MemoryCell::with_data(skip, 0));
+
+			generate_expr(program, ast, fnc, &args[1])?;
+			let end_label = program.code.len();
+			program.code.push(MemoryCell::with_data(OpCode::Jmp, 0));
+
+			program.code[skip_label] = MemoryCell::with_data(skip, program.code.len() as u16);
+			program.code.push(MemoryCell::plain_inst(OpCode::Const4));
+			program.code.push(MemoryCell::raw(skipped_value));
+
+			program.code[end_label] = MemoryCell::with_data(OpCode::Jmp, program.code.len() as u16);
+		}
+		Expr::FuncCall(func) if is_aggregate_comparison(func) => {
+			let (a, b) = (&func.1[0], &func.1[1]);
+			generate_expr(program, ast, fnc, a)?;
+			generate_expr(program, ast, fnc, b)?;
+
+			let tk = typekind_of(a)?;
+			let size = tk.size();
+			let mut parts = Vec::new();
+			comparable_parts(&tk, 0, &mut parts);
+
+			// compare the parts one by one, `and`ing the results together on top of both values
+			let and_fn = get_builtin_fn(expr.span(), "__op_binary_and", &[TypeKind::Bool, TypeKind::Bool])?;
+			for (i, (offset, part)) in parts.iter().enumerate() {
+				let top = 2 * size + if i > 0 { 4 } else { 0 };
+				let words = part.size() / 4;
+
+				for _ in 0..words {
+					program
+						.code
+						.push(MemoryCell::with_data(OpCode::Peek4, (top - offset - 4) as u16));
+				}
+				for _ in 0..words {
+					program
+						.code
+						.push(MemoryCell::with_data(OpCode::Peek4, (top + words * 4 - size - offset - 4) as u16));
+				}
+
+				let eq_fn = get_builtin_fn(expr.span(), "__op_binary_equality", &[part.clone(), part.clone()])?;
+				program
+					.code
+					.push(MemoryCell::with_data(OpCode::CallBuiltIn, eq_fn as u16));
+				if i > 0 {
+					program
+						.code
+						.push(MemoryCell::with_data(OpCode::CallBuiltIn, and_fn as u16));
+				}
+			}
+			if parts.is_empty() {
+				program.code.push(MemoryCell::plain_inst(OpCode::Const4));
+				program.code.push(MemoryCell::raw(1));
+			}
+
+			program
+				.code
+				.push(MemoryCell::with_data(OpCode::Discard, (2 * size) as u16));
+			program.code.push(MemoryCell::raw(4));
+
+			if func.0.raw.as_str() == "__op_binary_not_equal" {
+				let not_fn = get_builtin_fn(expr.span(), "__op_unary_not", &[TypeKind::Bool])?;
+				program
+					.code
+					.push(MemoryCell::with_data(OpCode::CallBuiltIn, not_fn as u16));
+			}
+		}
+		Expr::FuncCall(func) if array_length(func).is_some() => {
+			program.code.push(MemoryCell::plain_inst(OpCode::Const4));
+			program.code.push(MemoryCell::raw(array_length(func).unwrap() as u32));
+		}
 		Expr::FuncCall((id, args)) => {
 			for arg in args {
-				generate_expr(program, ast, fnc, arg);
+				generate_expr(program, ast, fnc, arg)?;
 			}
 
-			let arg_types = &args.iter().map(|e| e.typekind().unwrap()).collect::<Vec<_>>();
+			let arg_types = &args.iter().map(|e| typekind_of(e)).collect::<Result<Vec<_>, _>>()?;
+
+			// calls to user defined functions are resolved to their signature
+			let resolved = id.resolved.as_ref().map(|(key, _)| key.as_str()).unwrap_or("");
 
-			if let Some((func, _)) = crate::builtins::get_builtin_fn(id.raw.as_ref(), arg_types) {
+			if let Some(func) = program.data.functions.get(resolved) {
+				let address = func.address.ok_or_else(|| {
+					CompileError::internal(id.raw.just_span(), format!("`{}` has not been generated yet", id.raw.item))
+				})?;
 				program
 					.code
-					.push(MemoryCell::with_data(OpCode::CallBuiltIn, func as u16));
-			} else if let Some(func) = program.data.functions.get(id.raw.as_str()) {
+					.push(MemoryCell::with_data(OpCode::Call, address as u16));
 				program
 					.code
-					.push(MemoryCell::with_data(OpCode::Call, func.address.unwrap() as u16));
+					.push(MemoryCell::raw(func.param_types.iter().map(|t| t.size() as u32).sum()));
+			} else if let Some((func, _)) = crate::builtins::get_builtin_fn(id.raw.as_ref(), arg_types) {
 				program
 					.code
-					.push(MemoryCell::raw(func.param_types.iter().map(|t| t.size() as u32).sum()));
+					.push(MemoryCell::with_data(OpCode::CallBuiltIn, func as u16));
 			} else {
-				panic!("Unrecognized function: {:?}: ({:?})", id, arg_types);
+				return Err(CompileError::UnknownFunction(id.raw.clone(), arg_types.clone()));
 			}
 		}
 		Expr::Literal(l) => match l.item {
@@ -234,19 +754,29 @@ pub fn generate_expr(program: &mut VMProgram, ast: &Program, fnc: &FuncMeta, exp
 					.code
 					.push(MemoryCell::raw(unsafe { std::mem::transmute(i as i32) }));
 			}
+			Literal::BoolLiteral(b) => {
+				program.code.push(MemoryCell::plain_inst(OpCode::Const4));
+				program.code.push(MemoryCell::raw(b as u32));
+			}
+			Literal::UnsignedIntegerLiteral(i) => {
+				program.code.push(MemoryCell::plain_inst(OpCode::Const4));
+				program.code.push(MemoryCell::raw(i as u32));
+			}
 		},
 		Expr::Symbol(s) => {
 			let symbol = {
-				if let Some(symbol) = fnc.symbols.get(s.raw.as_str()) {
+				if let Some(symbol) = fnc.lookup(s.raw.as_str()) {
 					symbol
 				} else if let Some(symbol) = program.data.global_symbols.get(s.raw.as_str()) {
 					symbol
 				} else {
-					panic!("Unknown symbol: {:?}", s);
+					return Err(CompileError::UnknownSymbol(s.raw.clone()));
 				}
 			};
 
-			let offset = symbol.stack_offset.unwrap();
+			let offset = symbol
+				.stack_offset
+				.ok_or_else(|| CompileError::internal(s.raw.just_span(), "symbol has no storage"))?;
 			let size = symbol.type_kind.size();
 
 			let instruction = match symbol.is_static {
@@ -260,38 +790,70 @@ pub fn generate_expr(program: &mut VMProgram, ast: &Program, fnc: &FuncMeta, exp
 					.push(MemoryCell::with_data(instruction, (offset + (i * 4)) as u16))
 			}
 		}
-		Expr::Grouped(e) => generate_expr(program, ast, fnc, e),
-		Expr::FieldAccess(s, f, t, so) => {
-            let outer = fnc.symbols.get(s.raw.as_str()).unwrap();
-            let stack_offset = outer.stack_offset.unwrap();
-            match &s.resolved.as_ref().unwrap().1 {
-				TypeKind::Struct(_) => {
-                    let so = stack_offset + so.unwrap();
-        
-                    for i in 0..(t.as_ref().unwrap().size() / 4) {
-                        program
-                            .code
-                            .push(MemoryCell::with_data(OpCode::Load4, (so + (i * 4)) as u16))
-                    }
-                }
-                TypeKind::Vector(_, _) => {
-                    let xyzw = "xyzw";
-                    let rgba = "rgba";
-                    for c in f.chars() {
-                        let i = xyzw.find(c).unwrap_or_else(|| rgba.find(c).unwrap());
-                        program.code.push(MemoryCell::with_data(OpCode::Load4, stack_offset as u16 + (i as u16 * 4)));
-                    }
-                }
-                _ => panic!("Unexpected type")
-            }
-		}
-		Expr::StructConstruction(_, s, fields) => {
-			let decl = s.as_ref().unwrap().borrow();
-
-			for (name, _) in decl.members.iter() {
-				let (_, field_expr) = fields.iter().find(|(f, _)| &f.item == &name.item).unwrap();
-				generate_expr(program, ast, fnc, field_expr);
+		Expr::Grouped(e) => generate_expr(program, ast, fnc, e)?,
+		Expr::Conversion(e, tk) => {
+			generate_expr(program, ast, fnc, e)?;
+
+			// conversions are named like the type they convert to, e.g. `Float(Int)`
+			let conversion = get_builtin_fn(expr.span(), &tk.to_string(), &[typekind_of(e)?])?;
+			program
+				.code
+				.push(MemoryCell::with_data(OpCode::CallBuiltIn, conversion as u16));
+		}
+		Expr::FieldAccess(e, f, t, so) => {
+			if let Some(place) = generate_place(program, ast, fnc, expr)? {
+				load_place(program, place);
+			} else {
+				// the value is a temporary, so copy the selected words from the top of the stack and drop the rest
+				generate_expr(program, ast, fnc, e)?;
+
+				let base = typekind_of(e)?;
+				let size = base.size();
+				let words = field_words(&base, f, t, so)?;
+
+				for (i, w) in words.iter().enumerate() {
+					program
+						.code
+						.push(MemoryCell::with_data(OpCode::Peek4, (size + i * 4 - 4 - w * 4) as u16));
+				}
+				program.code.push(MemoryCell::with_data(OpCode::Discard, size as u16));
+				program.code.push(MemoryCell::raw(words.len() as u32 * 4));
+			}
+		}
+		Expr::Index(e, index, brackets, _) => {
+			if let Some(place) = generate_place(program, ast, fnc, expr)? {
+				load_place(program, place);
+			} else {
+				let (element_size, len) = match typekind_of(e)? {
+					TypeKind::Array(element, len) => (element.size(), len),
+					_ => return Err(CompileError::internal(*brackets, "indexed value is not an array")),
+				};
+
+				generate_expr(program, ast, fnc, e)?;
+				generate_expr(program, ast, fnc, index)?;
+				program.code.push(MemoryCell::with_data(OpCode::Extract, len as u16));
+				program.code.push(MemoryCell::raw(element_size as u32));
+			}
+		}
+		Expr::ArrayLiteral(_, elements) => {
+			for e in elements {
+				generate_expr(program, ast, fnc, e)?;
+			}
+		}
+		Expr::StructConstruction(name, s, fields) => {
+			let decl = s
+				.as_ref()
+				.ok_or_else(|| CompileError::internal(name.just_span(), "struct was not resolved"))?
+				.borrow();
+
+			for (member, _) in decl.members.iter() {
+				let (_, field_expr) = fields.iter().find(|(f, _)| f.item == member.item).ok_or_else(|| {
+					CompileError::internal(name.just_span(), format!("missing field `{}`", member.item))
+				})?;
+				generate_expr(program, ast, fnc, field_expr)?;
 			}
 		}
 	}
+
+	Ok(())
 }
@@ -1,6 +1,6 @@
 use crate::ast::TypeKind;
 
-use crate::ast::StructDeclaration;
+use crate::{ast::StructDeclaration, compiler::control_flow::Warning};
 use std::{cell::RefCell, collections::HashMap, rc::Rc};
 
 #[derive(Clone, Debug)]
@@ -13,31 +13,81 @@ pub struct SymbolMeta {
 
 #[derive(Clone, Debug)]
 pub struct FuncMeta {
+	/// Every parameter and variable declared in the function, for inspecting the stack while debugging. Use `lookup`
+	/// to find the ones that are in scope.
 	pub symbols: HashMap<String, SymbolMeta>,
+	/// The parameters and variables currently in scope, innermost block last. The first scope holds the parameters.
+	pub scopes: Vec<HashMap<String, SymbolMeta>>,
 	pub address: Option<usize>,
 	pub stack_offset: usize,
 	pub return_type: Option<TypeKind>,
 	pub param_types: Vec<TypeKind>,
+	/// The loops around the code being generated, innermost last.
+	pub loops: Vec<LoopLabels>,
+}
+
+/// The jumps out of a loop that is being generated, which are patched once the loop is complete.
+#[derive(Clone, Debug, Default)]
+pub struct LoopLabels {
+	/// The number of scopes outside of the loop body. The variables of deeper scopes are freed before jumping.
+	pub depth: usize,
+	/// The addresses of the `Jmp`s generated for `break` statements.
+	pub breaks: Vec<usize>,
+	/// The addresses of the `Jmp`s generated for `continue` statements.
+	pub continues: Vec<usize>,
 }
 
 impl FuncMeta {
 	pub fn new() -> Self {
 		FuncMeta {
 			symbols: HashMap::new(),
+			scopes: vec![HashMap::new()],
 			address: None,
 			stack_offset: 0,
 			return_type: None,
 			param_types: Vec::new(),
+			loops: Vec::new(),
 		}
 	}
+
+	pub fn push_scope(&mut self) {
+		self.scopes.push(HashMap::new());
+	}
+
+	/// Leaves the innermost scope, returning the symbols declared in it.
+	pub fn pop_scope(&mut self) -> HashMap<String, SymbolMeta> {
+		self.scopes.pop().expect("popped the parameter scope of a function")
+	}
+
+	/// Declares a symbol in the innermost scope.
+	pub fn declare(&mut self, ident: &str, symbol: SymbolMeta) {
+		self.symbols.insert(ident.to_owned(), symbol.clone());
+		self.scopes.last_mut().unwrap().insert(ident.to_owned(), symbol);
+	}
+
+	/// Finds the symbol a name refers to, searching from the innermost scope outwards.
+	pub fn lookup(&self, ident: &str) -> Option<&SymbolMeta> {
+		self.scopes.iter().rev().find_map(|scope| scope.get(ident))
+	}
+}
+
+/// The key of a user defined function in `ProgramData::functions`, e.g. `f(Float, Vec3)`, so that overloads of the
+/// same name don't collide.
+pub fn signature(ident: &str, param_types: &[TypeKind]) -> String {
+	let params = param_types.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+
+	format!("{}({})", ident, params.join(", "))
 }
 
 #[derive(Clone, Debug)]
 pub struct ProgramData {
+	/// User defined functions, keyed by their `signature`.
 	pub functions: HashMap<String, FuncMeta>,
 	pub global_symbols: HashMap<String, SymbolMeta>,
 	pub struct_declarations: HashMap<String, Rc<RefCell<StructDeclaration>>>,
 	pub static_section_size: usize,
+	/// Problems found while compiling the program that don't stop it from being compiled.
+	pub warnings: Vec<Warning>,
 }
 
 impl ProgramData {
@@ -47,6 +97,7 @@ impl ProgramData {
 			global_symbols: HashMap::new(),
 			struct_declarations: HashMap::new(),
 			static_section_size: 0,
+			warnings: Vec::new(),
 		}
 	}
 }
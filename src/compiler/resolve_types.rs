@@ -1,12 +1,25 @@
 use crate::{
 	ast::*,
-	compiler::program_data::{FuncMeta, ProgramData, SymbolMeta},
+	compiler::{
+		control_flow,
+		program_data::{signature, FuncMeta, ProgramData, SymbolMeta},
+	},
 };
 
 #[derive(Debug)]
 pub struct ResolveTypes<'a> {
 	program_data: &'a mut ProgramData,
-	current_scope: Option<Ident>,
+	/// The signature of the function being resolved.
+	current_scope: Option<String>,
+	/// Where the parameters and variables currently in scope were declared, innermost block last.
+	declarations: Vec<HashMap<Ident, Spanned<()>>>,
+	/// Where the variables of blocks that already ended were declared, to explain why they can't be found.
+	out_of_scope: HashMap<Ident, Spanned<()>>,
+	/// The user defined functions called by each function, keyed by signature, with the call sites.
+	calls: HashMap<String, Vec<(String, Spanned<Ident>)>>,
+	/// The number of loops around the statement being resolved.
+	loop_depth: usize,
+	errors: Vec<TypeError>,
 }
 
 impl<'a> ResolveTypes<'a> {
@@ -14,6 +27,11 @@ impl<'a> ResolveTypes<'a> {
 		ResolveTypes {
 			program_data,
 			current_scope: None,
+			declarations: Vec::new(),
+			out_of_scope: HashMap::new(),
+			calls: HashMap::new(),
+			loop_depth: 0,
+			errors: Vec::new(),
 		}
 	}
 }
@@ -23,16 +41,233 @@ pub enum TypeError {
 	UnknownSymbol(Spanned<Ident>),
 	UnknownFunction(Spanned<Ident>, Vec<TypeKind>),
 	AssignmentToImmutable(Spanned<Ident>),
-	TypeError(Spanned<Ident>, TypeKind, TypeKind),
+	/// The expression at the span was expected to have the first type, but has the second.
+	TypeMismatch(Spanned<()>, TypeKind, TypeKind),
 	UnknownType(Spanned<Ident>),
-	GenericError(String), // For now
+	UnknownField(Spanned<Ident>, TypeKind),
+	InvalidSwizzle(Spanned<Ident>),
+	InvalidFieldAccess(Spanned<Ident>, TypeKind),
+	/// A struct construction at the span is missing the named field.
+	MissingField(Spanned<Ident>, Ident),
+	/// A swizzle on the left of an assignment names the same component twice.
+	DuplicateSwizzleComponent(Spanned<Ident>),
+	/// The name is defined a second time, the second span is the previous definition. The string describes what kind
+	/// of item it is, e.g. `function`.
+	Redefinition(Spanned<Ident>, Spanned<()>, &'static str),
+	/// A variable was used after the block it was declared in (at the second span) ended.
+	OutOfScope(Spanned<Ident>, Spanned<()>),
+	/// A function was called with a number of arguments that none of its overloads take.
+	WrongArgumentCount(Spanned<Ident>, Vec<usize>, usize),
+	/// A call matches more than one function, the candidates are described like ``builtin `f(Float)` ``.
+	AmbiguousCall(Spanned<Ident>, Vec<String>),
+	/// A function calls itself, directly or through the other functions in the cycle of signatures, which starts and
+	/// ends with the recursive function.
+	Recursion(Spanned<Ident>, Vec<String>),
+	/// The struct contains itself through the field whose type is at the second span, so it would be infinitely large.
+	RecursiveStruct(Spanned<Ident>, Spanned<()>),
+	/// An opaque type like `Sampler2D` was used at the span where only plain data can be stored. The string describes
+	/// the use, e.g. `a variable`.
+	OpaqueType(Spanned<()>, TypeKind, &'static str),
+	/// The step of a `for` loop is not a nonzero constant.
+	InvalidLoopStep(Spanned<()>),
+	/// A `break` or `continue`, named by the string, is not inside of a loop.
+	JumpOutsideLoop(Spanned<()>, &'static str),
+	/// The function can reach the end of its body without returning a value of the return type.
+	MissingReturn(Spanned<Ident>, Spanned<TypeKind>),
+	/// `Void` was used at the span where a value is needed. The string describes the use, e.g. `a variable`.
+	VoidValue(Spanned<()>, &'static str),
+	/// A field of the struct returned from `main`, whose type is at the first span, can't be a shader output. The
+	/// second span is the return type of `main`.
+	InvalidOutput(Spanned<()>, TypeKind, Spanned<()>),
+	/// The expression at the span was indexed, but its type is not an array.
+	NotIndexable(Spanned<()>, TypeKind),
+	/// A constant index is outside of the array's length.
+	IndexOutOfBounds(Spanned<()>, i64, usize),
 }
 
-use std::{cell::RefCell, error, fmt, rc::Rc};
+use crate::diagnostic::Diagnostic;
+use std::{cell::RefCell, collections::HashMap, error, fmt, rc::Rc};
+
+fn operator_symbol(ident: &str) -> Option<&'static str> {
+	Some(match ident {
+		"__op_unary_neg" => "-",
+		"__op_unary_not" => "!",
+		"__op_unary_bitnot" => "~",
+		"__op_binary_add" => "+",
+		"__op_binary_sub" => "-",
+		"__op_binary_mul" => "*",
+		"__op_binary_div" => "/",
+		"__op_binary_mod" => "%",
+		"__op_binary_bitand" => "&",
+		"__op_binary_bitor" => "|",
+		"__op_binary_bitxor" => "^",
+		"__op_binary_shl" => "<<",
+		"__op_binary_shr" => ">>",
+		"__op_binary_less" => "<",
+		"__op_binary_less_equal" => "<=",
+		"__op_binary_greater" => ">",
+		"__op_binary_greater_equal" => ">=",
+		"__op_binary_equality" => "==",
+		"__op_binary_not_equal" => "!=",
+		"__op_binary_and" => "&&",
+		"__op_binary_or" => "||",
+		"__op_binary_xor" => "^^",
+		_ => return None,
+	})
+}
+
+fn type_list(types: &[TypeKind]) -> String {
+	types.iter().map(|t| t.to_string()).collect::<Vec<_>>().join(", ")
+}
+
+impl From<TypeError> for Diagnostic {
+	fn from(e: TypeError) -> Diagnostic {
+		match e {
+			TypeError::UnknownSymbol(s) => Diagnostic::error(format!("unknown symbol `{}`", s.item))
+				.with_code("E0301")
+				.with_primary(s.just_span(), "not found in this scope"),
+			TypeError::UnknownFunction(f, args) => match operator_symbol(&f.item) {
+				Some(op) => Diagnostic::error(format!("operator `{}` is not defined for ({})", op, type_list(&args)))
+					.with_code("E0302")
+					.with_primary(f.just_span(), "no matching operator"),
+				None => Diagnostic::error(format!("no function `{}` taking ({})", f.item, type_list(&args)))
+					.with_code("E0302")
+					.with_primary(f.just_span(), "no matching function"),
+			},
+			TypeError::AssignmentToImmutable(s) => {
+				Diagnostic::error(format!("cannot assign twice to immutable variable `{}`", s.item))
+					.with_code("E0303")
+					.with_primary(s.just_span(), "cannot assign to immutable variable")
+					.with_note("variables have to be declared with `let mut` to be assignable")
+			}
+			TypeError::TypeMismatch(span, expected, found) => Diagnostic::error("mismatched types")
+				.with_code("E0304")
+				.with_primary(span, format!("expected `{}`, found `{}`", expected, found)),
+			TypeError::UnknownType(t) => Diagnostic::error(format!("unknown type `{}`", t.item))
+				.with_code("E0305")
+				.with_primary(t.just_span(), "not a builtin type or declared struct"),
+			TypeError::UnknownField(f, tk) => {
+				Diagnostic::error(format!("no field `{}` on type `{}`", f.item, tk))
+					.with_code("E0306")
+					.with_primary(f.just_span(), "unknown field")
+			}
+			TypeError::InvalidSwizzle(f) => Diagnostic::error(format!("`{}` is not a valid vector swizzle", f.item))
+				.with_code("E0307")
+				.with_primary(f.just_span(), "invalid swizzle")
+				.with_note("swizzles take up to four components from either `xyzw` or `rgba`"),
+			TypeError::InvalidFieldAccess(f, tk) => {
+				Diagnostic::error(format!("type `{}` does not have fields", tk))
+					.with_code("E0308")
+					.with_primary(f.just_span(), "field access on a type without fields")
+			}
+			TypeError::MissingField(s, f) => {
+				Diagnostic::error(format!("missing field `{}` in construction of `{}`", f, s.item))
+					.with_code("E0309")
+					.with_primary(s.just_span(), format!("missing `{}`", f))
+			}
+			TypeError::DuplicateSwizzleComponent(f) => {
+				Diagnostic::error(format!("swizzle `{}` assigns to a component more than once", f.item))
+					.with_code("E0310")
+					.with_primary(f.just_span(), "repeated component")
+			}
+			TypeError::Redefinition(i, previous, kind) => {
+				Diagnostic::error(format!("the {} `{}` is defined multiple times", kind, i.item))
+					.with_code("E0314")
+					.with_primary(i.just_span(), "redefined here")
+					.with_secondary(previous, "previous definition here")
+			}
+			TypeError::OutOfScope(s, declared) => Diagnostic::error(format!("`{}` is not in scope", s.item))
+				.with_code("E0316")
+				.with_primary(s.just_span(), "used outside of the block it was declared in")
+				.with_secondary(declared, "declared here"),
+			TypeError::WrongArgumentCount(f, expected, found) => {
+				let arguments = |n: usize| if n == 1 { "argument" } else { "arguments" };
+				let takes = match expected[..] {
+					[n] => format!("{} {}", n, arguments(n)),
+					_ => {
+						let counts = expected.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+						format!("{} arguments", counts.join(" or "))
+					}
+				};
+
+				Diagnostic::error(format!(
+					"`{}` takes {} but {} {} supplied",
+					f.item,
+					takes,
+					found,
+					if found == 1 { "was" } else { "were" }
+				))
+				.with_code("E0315")
+				.with_primary(f.just_span(), format!("called with {} {}", found, arguments(found)))
+			}
+			TypeError::AmbiguousCall(f, candidates) => Diagnostic::error(format!("call to `{}` is ambiguous", f.item))
+				.with_code("E0313")
+				.with_primary(f.just_span(), "matches more than one function")
+				.with_note(format!("candidates: {}", candidates.join(", "))),
+			TypeError::Recursion(f, cycle) => {
+				let diagnostic = Diagnostic::error(format!("recursive call to `{}`", f.item))
+					.with_code("E0317")
+					.with_primary(f.just_span(), "recursion is not supported");
+
+				let diagnostic = if cycle.len() > 2 {
+					let cycle = cycle.iter().map(|f| format!("`{}`", f)).collect::<Vec<_>>();
+					diagnostic.with_note(format!("call cycle: {}", cycle.join(" -> ")))
+				} else {
+					diagnostic
+				};
+				diagnostic.with_note("GLSL does not allow functions to call themselves, directly or indirectly")
+			}
+			TypeError::RecursiveStruct(s, field) => {
+				Diagnostic::error(format!("recursive struct `{}` has infinite size", s.item))
+					.with_code("E0318")
+					.with_primary(s.just_span(), "recursive struct")
+					.with_secondary(field, "contains the struct itself")
+			}
+			TypeError::OpaqueType(span, tk, usage) => {
+				Diagnostic::error(format!("the opaque type `{}` cannot be used as {}", tk, usage))
+					.with_code("E0319")
+					.with_primary(span, "opaque type")
+					.with_note("samplers can only be `uniform` inputs or function parameters")
+			}
+			TypeError::InvalidLoopStep(span) => Diagnostic::error("loop step must be a nonzero constant")
+				.with_code("E0320")
+				.with_primary(span, "not a nonzero constant")
+				.with_note("the sign of the step decides whether the loop counts up or down"),
+			TypeError::JumpOutsideLoop(span, keyword) => Diagnostic::error(format!("`{}` outside of a loop", keyword))
+				.with_code("E0321")
+				.with_primary(span, format!("cannot `{}` outside of a loop", keyword)),
+			TypeError::MissingReturn(f, ret_type) => {
+				Diagnostic::error(format!("function `{}` does not return a value on every path", f.item))
+					.with_code("E0322")
+					.with_primary(f.just_span(), "can reach the end without returning")
+					.with_secondary(ret_type.just_span(), format!("expected `{}` because of this", ret_type.item))
+					.with_note("only functions returning `Void` can end without a `return`")
+			}
+			TypeError::VoidValue(span, usage) => Diagnostic::error(format!("`Void` cannot be used as {}", usage))
+				.with_code("E0323")
+				.with_primary(span, "`Void` has no value"),
+			TypeError::InvalidOutput(span, tk, ret_type) => {
+				Diagnostic::error(format!("`{}` cannot be a shader output", tk))
+					.with_code("E0324")
+					.with_primary(span, "not a valid output type")
+					.with_secondary(ret_type, "each field of the struct returned from `main` becomes an output")
+					.with_note("outputs must be `Float`, `Int` or `UInt` scalars or vectors, or arrays of them")
+			}
+			TypeError::NotIndexable(span, tk) => Diagnostic::error(format!("cannot index into a value of type `{}`", tk))
+				.with_code("E0311")
+				.with_primary(span, "not an array"),
+			TypeError::IndexOutOfBounds(span, index, len) => {
+				Diagnostic::error(format!("index {} is out of bounds for an array of length {}", index, len))
+					.with_code("E0312")
+					.with_primary(span, "out of bounds")
+			}
+		}
+	}
+}
 
 impl fmt::Display for TypeError {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-		write!(f, "Type Error: {:?}", self)
+		write!(f, "{}", Diagnostic::from(self.clone()))
 	}
 }
 
@@ -49,38 +284,416 @@ impl<'a> ResolveTypes<'a> {
 
 		self.program_data.functions.get_mut(&fn_name).unwrap()
 	}
+
+	/// Records an error, every error found while resolving goes through here.
+	fn error(&mut self, e: TypeError) {
+		self.errors.push(e);
+	}
+
+	/// The numbers of parameters the builtin and user defined overloads of a function take.
+	fn arities(&self, name: &str) -> Vec<usize> {
+		let prefix = format!("{}(", name);
+		let user = self
+			.program_data
+			.functions
+			.iter()
+			.filter(|(key, _)| key.starts_with(&prefix))
+			.map(|(_, f)| f.param_types.len());
+		let builtin = crate::builtins::functions::FUNCTIONS
+			.iter()
+			.filter(|f| f.ident() == name)
+			.map(|f| f.arg_types().len());
+
+		user.chain(builtin).collect()
+	}
+
+	/// Adds a call to the user defined function with the signature `key` to the call graph.
+	fn record_call(&mut self, key: &str, site: &Spanned<Ident>) {
+		let caller = self.current_scope.clone().unwrap();
+		self.calls
+			.entry(caller)
+			.or_default()
+			.push((key.to_owned(), site.clone()));
+	}
+
+	/// The functions named `name` which the arguments can be implicitly converted to, keeping only the ones that need
+	/// the fewest conversions.
+	fn conversion_candidates(&self, name: &str, arg_types: &[TypeKind]) -> Vec<Candidate> {
+		let broadcast = broadcasts(name);
+		let conversions = |param_types: &[TypeKind]| -> Option<Vec<Vec<TypeKind>>> {
+			if param_types.len() != arg_types.len() {
+				return None;
+			}
+			arg_types
+				.iter()
+				.zip(param_types)
+				.map(|(from, to)| implicit_conversion(from, to, broadcast))
+				.collect()
+		};
+
+		let prefix = format!("{}(", name);
+		let user = self
+			.program_data
+			.functions
+			.iter()
+			.filter(|(key, _)| key.starts_with(&prefix))
+			.filter_map(|(key, f)| {
+				Some(Candidate {
+					resolved: key.clone(),
+					return_type: f.return_type.clone().unwrap(),
+					conversions: conversions(&f.param_types)?,
+					signature: key.clone(),
+					description: format!("`{}`", key),
+				})
+			});
+		let builtin = crate::builtins::functions::FUNCTIONS
+			.iter()
+			.filter(|f| f.ident() == name)
+			.filter_map(|f| {
+				Some(Candidate {
+					resolved: name.to_owned(),
+					return_type: f.return_type(),
+					conversions: conversions(&f.arg_types())?,
+					signature: signature(name, &f.arg_types()),
+					description: format!("builtin `{}`", signature(name, &f.arg_types())),
+				})
+			});
+
+		let candidates = user.chain(builtin).collect::<Vec<_>>();
+		let cost = |c: &Candidate| c.conversions.iter().map(Vec::len).sum::<usize>();
+		let fewest = candidates.iter().map(cost).min();
+
+		candidates.into_iter().filter(|c| Some(cost(c)) == fewest).collect()
+	}
+
+	/// Declares a parameter or variable in the innermost scope. Names can't be shadowed inside of a function, so it is
+	/// an error if the name is already in scope.
+	fn declare(&mut self, ident: &Spanned<Ident>, symbol: SymbolMeta, kind: &'static str) {
+		let previous = self.declarations.iter().rev().find_map(|scope| scope.get(&ident.item)).cloned();
+		if let Some(previous) = previous {
+			self.error(TypeError::Redefinition(ident.clone(), previous, kind));
+			return;
+		}
+
+		self.declarations
+			.last_mut()
+			.unwrap()
+			.insert(ident.item.clone(), ident.just_span());
+		self.current_scope().declare(&ident.item, symbol);
+	}
+
+	fn push_scope(&mut self) {
+		self.declarations.push(HashMap::new());
+		self.current_scope().push_scope();
+	}
+
+	fn pop_scope(&mut self) {
+		let declarations = self.declarations.pop().unwrap();
+		self.out_of_scope.extend(declarations);
+		self.current_scope().pop_scope();
+	}
+
+	/// Registers every struct and function signature before any function is resolved, so that items can be used
+	/// regardless of the order they are declared in.
+	fn declare_items(&mut self, ast: &mut Program) -> VResult {
+		for e in sort_structs(&mut ast.struct_declarations) {
+			self.error(e);
+		}
+		ast.struct_declarations.visit(self)?;
+
+		for func in ast.functions.iter_mut() {
+			for (tk, _) in &mut func.params {
+				tk.item.visit(self)?;
+				if is_void(&tk.item) {
+					self.error(TypeError::VoidValue(tk.just_span(), "a parameter"));
+				}
+			}
+			func.ret_type.item.visit(self)?;
+			if let TypeKind::Array(_, _) = func.ret_type.item {
+				if is_void(&func.ret_type.item) {
+					self.error(TypeError::VoidValue(func.ret_type.just_span(), "an array element"));
+				}
+			}
+			if is_opaque(&func.ret_type.item) {
+				let ret_type = &func.ret_type;
+				self.error(TypeError::OpaqueType(ret_type.just_span(), ret_type.item.clone(), "a return type"));
+			}
+
+			let mut fnc = FuncMeta::new();
+			fnc.return_type = Some(func.ret_type.item.clone());
+			fnc.param_types = func.params.iter().map(|x| x.0.item.clone()).collect();
+			self.program_data
+				.functions
+				.insert(signature(&func.ident.item, &fnc.param_types), fnc);
+		}
+
+		Ok(())
+	}
+
+	/// Sorts the functions so that every function comes after the functions it calls, which is the order GLSL
+	/// requires and lets codegen know the address of every callee. Recursion makes that impossible, GLSL doesn't
+	/// support it either, so it is reported instead.
+	fn sort_functions(&mut self, functions: &mut Vec<FunctionDeclaration>) {
+		#[derive(Clone, PartialEq)]
+		enum State {
+			Unvisited,
+			InProgress,
+			Done,
+		}
+
+		struct Walk<'b> {
+			calls: &'b HashMap<String, Vec<(String, Spanned<Ident>)>>,
+			index: HashMap<String, usize>,
+			state: Vec<State>,
+			path: Vec<String>,
+			order: Vec<usize>,
+			errors: Vec<TypeError>,
+		}
+
+		impl<'b> Walk<'b> {
+			fn visit(&mut self, key: &str) {
+				let i = self.index[key];
+				self.state[i] = State::InProgress;
+				self.path.push(key.to_owned());
+
+				for (callee, site) in self.calls.get(key).into_iter().flatten() {
+					match self.state[self.index[callee.as_str()]] {
+						State::Unvisited => self.visit(callee),
+						State::InProgress => {
+							let start = self.path.iter().position(|f| f == callee).unwrap();
+							let mut cycle = self.path[start..].to_vec();
+							cycle.push(callee.clone());
+							self.errors.push(TypeError::Recursion(site.clone(), cycle));
+						}
+						State::Done => {}
+					}
+				}
+
+				self.path.pop();
+				self.state[i] = State::Done;
+				self.order.push(i);
+			}
+		}
+
+		let keys = functions
+			.iter()
+			.map(|f| {
+				let param_types = f.params.iter().map(|(tk, _)| tk.item.clone()).collect::<Vec<_>>();
+				signature(&f.ident.item, &param_types)
+			})
+			.collect::<Vec<_>>();
+
+		let mut walk = Walk {
+			calls: &self.calls,
+			// duplicate definitions were already reported, the first one stands in for all of them
+			index: keys.iter().enumerate().rev().map(|(i, key)| (key.clone(), i)).collect(),
+			state: vec![State::Unvisited; keys.len()],
+			path: Vec::new(),
+			order: Vec::new(),
+			errors: Vec::new(),
+		};
+		for (i, key) in keys.iter().enumerate() {
+			if walk.state[i] == State::Unvisited && walk.index[key.as_str()] == i {
+				walk.visit(key);
+			}
+		}
+		for (i, key) in keys.iter().enumerate() {
+			if walk.index[key.as_str()] != i {
+				walk.order.push(i);
+			}
+		}
+
+		let Walk { order, errors, .. } = walk;
+		for e in errors {
+			self.error(e);
+		}
+
+		let mut slots = functions.drain(..).map(Some).collect::<Vec<_>>();
+		*functions = order.into_iter().map(|i| slots[i].take().unwrap()).collect();
+	}
+
+	/// Checks that `expr` has the type `expected`, unless the expression is already known to be erroneous.
+	fn expect_type(&mut self, expr: &Expr, expected: &TypeKind) {
+		let found = typekind_of(expr);
+		if !found.is_error() && !expected.is_error() && &found != expected {
+			self.error(TypeError::TypeMismatch(expr.span(), expected.clone(), found));
+		}
+	}
+}
+
+/// Resolves the names of builtin types, e.g. `Float`, `IVec3` or `Mat2x4`.
+fn builtin_type(name: &str) -> Option<TypeKind> {
+	let scalar = match name {
+		"Float" => Some(TypeKind::F32),
+		"Int" => Some(TypeKind::I32),
+		"UInt" => Some(TypeKind::U32),
+		"Bool" => Some(TypeKind::Bool),
+		"Sampler2D" => Some(TypeKind::Sampler2D),
+		_ => None,
+	};
+	if scalar.is_some() {
+		return scalar;
+	}
+
+	let dimension = |n: &str| n.parse::<usize>().ok().filter(|n| (2..=4).contains(n));
+
+	let vectors = [
+		("Vec", TypeKind::F32),
+		("IVec", TypeKind::I32),
+		("UVec", TypeKind::U32),
+		("BVec", TypeKind::Bool),
+	];
+	for (prefix, element) in vectors.iter() {
+		if let Some(n) = name.strip_prefix(prefix).and_then(dimension) {
+			return Some(TypeKind::Vector(Box::new(element.clone()), n));
+		}
+	}
+
+	let dimensions = name.strip_prefix("Mat")?;
+	let mut parts = dimensions.splitn(2, 'x');
+	let m = parts.next().and_then(dimension)?;
+	let n = match parts.next() {
+		Some(n) => dimension(n)?,
+		None => m,
+	};
+	Some(TypeKind::Matrix(Box::new(TypeKind::F32), m, n))
+}
+
+/// The component indices selected by a swizzle like `xy` or `bgr` on a vector with `n` components.
+pub fn swizzle_components(swizzle: &str, n: usize) -> Option<Vec<usize>> {
+	if swizzle.is_empty() || swizzle.len() > 4 {
+		return None;
+	}
+
+	["xyzw", "rgba"].iter().find_map(|set| {
+		swizzle
+			.chars()
+			.map(|c| set.find(c).filter(|i| *i < n))
+			.collect::<Option<Vec<_>>>()
+	})
+}
+
+/// The value of an index that is an integer literal.
+pub fn constant_index(index: &Expr) -> Option<i64> {
+	match index {
+		Expr::Literal(l) => match l.item {
+			Literal::IntegerLiteral(i) => Some(i),
+			Literal::UnsignedIntegerLiteral(i) => Some(i as i64),
+			_ => None,
+		},
+		Expr::Grouped(e) => constant_index(e),
+		_ => None,
+	}
+}
+
+/// The value of a constant loop step like `2`, `-0.5` or `(-1)`.
+pub fn constant_step(step: &Expr) -> Option<f64> {
+	match step {
+		Expr::Literal(l) => match l.item {
+			Literal::IntegerLiteral(i) => Some(i as f64),
+			Literal::UnsignedIntegerLiteral(i) => Some(i as f64),
+			Literal::DecimalLiteral(f) => Some(f),
+			_ => None,
+		},
+		Expr::FuncCall((id, args)) if id.raw.item == "__op_unary_neg" && args.len() == 1 => {
+			constant_step(&args[0]).map(|s| -s)
+		}
+		Expr::Grouped(e) => constant_step(e),
+		_ => None,
+	}
+}
+
+/// The types an implicit conversion from `from` to `to` goes through, one builtin conversion per step, or `None` if
+/// there is no implicit conversion. Integers are promoted to floats like in GLSL, and scalars are broadcast to vectors
+/// if `broadcast` is set.
+pub fn implicit_conversion(from: &TypeKind, to: &TypeKind, broadcast: bool) -> Option<Vec<TypeKind>> {
+	let is_integer = |tk: &TypeKind| *tk == TypeKind::I32 || *tk == TypeKind::U32;
+
+	match (from, to) {
+		_ if from == to => Some(vec![]),
+		(from, TypeKind::F32) if is_integer(from) => Some(vec![TypeKind::F32]),
+		(TypeKind::Vector(from, n), TypeKind::Vector(to, m)) if n == m && is_integer(from) && **to == TypeKind::F32 => {
+			Some(vec![TypeKind::Vector(to.clone(), *m)])
+		}
+		(TypeKind::Bool, TypeKind::Vector(element, _))
+		| (TypeKind::I32, TypeKind::Vector(element, _))
+		| (TypeKind::U32, TypeKind::Vector(element, _))
+		| (TypeKind::F32, TypeKind::Vector(element, _))
+			if broadcast =>
+		{
+			let mut steps = implicit_conversion(from, element, false)?;
+			steps.push(to.clone());
+			Some(steps)
+		}
+		_ => None,
+	}
+}
+
+/// Whether scalar arguments of the function may be broadcast to vectors, which GLSL allows for arithmetic operators.
+fn broadcasts(name: &str) -> bool {
+	matches!(
+		name,
+		"__op_binary_add" | "__op_binary_sub" | "__op_binary_mul" | "__op_binary_div" | "__op_binary_mod"
+	)
+}
+
+/// A function that a call can be resolved to after implicitly converting its arguments.
+struct Candidate {
+	/// The name the call resolves to, i.e. the signature for user defined functions.
+	resolved: Ident,
+	return_type: TypeKind,
+	/// The conversion steps for each argument.
+	conversions: Vec<Vec<TypeKind>>,
+	signature: String,
+	/// How the candidate is described in ambiguity errors.
+	description: String,
+}
+
+fn has_duplicate_components(swizzle: &str) -> bool {
+	swizzle.chars().enumerate().any(|(i, c)| swizzle.chars().take(i).any(|p| p == c))
+}
+
+/// Whether values of the type are handles to resources outside of the program, which GLSL only allows as uniforms
+/// and function parameters.
+fn is_opaque(tk: &TypeKind) -> bool {
+	match tk {
+		TypeKind::Sampler2D => true,
+		TypeKind::Array(element, _) => is_opaque(element),
+		_ => false,
+	}
+}
+
+/// Whether the type is `Void` or an array of it, neither of which has values.
+fn is_void(tk: &TypeKind) -> bool {
+	match tk {
+		TypeKind::Void => true,
+		TypeKind::Array(element, _) => is_void(element),
+		_ => false,
+	}
+}
+
+/// The type of an already visited expression. Expressions which could not be typed are treated as poisoned.
+fn typekind_of(e: &Expr) -> TypeKind {
+	e.typekind().unwrap_or(TypeKind::Error)
 }
 
 impl<'a> Visitor for ResolveTypes<'a> {
 	fn type_kind(&mut self, tk: &mut TypeKind) -> VResult {
 		match tk {
 			TypeKind::TypeRef(name) => {
-				if name.starts_with("Vec") {
-					if let Some(n) = name.chars().nth(3).and_then(|x| x.to_digit(10)) {
-						*tk = TypeKind::Vector(Box::new(TypeKind::F32), n as usize);
-					}
-				} else if name.starts_with("Mat") {
-					let m = name.chars().nth(3).and_then(|x| x.to_digit(10));
-					let n = name.chars().nth(5).and_then(|x| x.to_digit(10));
-					if let Some(m) = m {
-						if let Some(n) = n {
-							*tk = TypeKind::Matrix(Box::new(TypeKind::F32), m as usize, n as usize);
-						} else {
-							*tk = TypeKind::Matrix(Box::new(TypeKind::F32), m as usize, m as usize);
-						}
-					}
-				} else if &name.item == "Float" {
-					*tk = TypeKind::F32;
-				} else if &name.item == "Int" {
-					*tk = TypeKind::I32;
+				if let Some(builtin) = builtin_type(&name.item) {
+					*tk = builtin;
 				} else if let Some(s) = self.program_data.struct_declarations.get(&name.item) {
 					*tk = TypeKind::Struct(s.clone());
 				} else {
-					Err(Box::new(TypeError::UnknownType(name.clone())))?;
+					let name = name.clone();
+					self.error(TypeError::UnknownType(name));
+					*tk = TypeKind::Error;
 				}
 
 				Ok(())
 			}
+			TypeKind::Array(element, _) => self.type_kind(element),
 			_ => Ok(()),
 		}
 	}
@@ -89,17 +702,31 @@ impl<'a> Visitor for ResolveTypes<'a> {
 		// it's impossible to parse a symbol outside of a function, so this is safe
 		let scope = self.current_scope();
 
-		if let Some(def) = scope.symbols.get(s.raw.item.as_str()) {
+		if let Some(def) = scope.lookup(s.raw.item.as_str()) {
 			s.resolved = Some((s.raw.item.clone(), def.type_kind.clone()));
 		} else if let Some(def) = self.program_data.global_symbols.get(s.raw.item.as_str()) {
 			s.resolved = Some((s.raw.item.clone(), def.type_kind.clone()));
+		} else if let Some(declared) = self.out_of_scope.get(s.raw.item.as_str()) {
+			let declared = *declared;
+			self.error(TypeError::OutOfScope(s.raw.clone(), declared));
+			s.resolved = Some((s.raw.item.clone(), TypeKind::Error));
 		} else {
-			Err(Box::new(TypeError::UnknownSymbol(s.raw.clone())))?;
+			self.error(TypeError::UnknownSymbol(s.raw.clone()));
+			s.resolved = Some((s.raw.item.clone(), TypeKind::Error));
 		}
 		Ok(())
 	}
 
 	fn struct_declaration(&mut self, s: &mut StructDeclaration) -> VResult {
+		for (_, tk) in &s.members {
+			if is_opaque(&tk.item) {
+				self.error(TypeError::OpaqueType(tk.just_span(), tk.item.clone(), "a struct field"));
+			}
+			if is_void(&tk.item) {
+				self.error(TypeError::VoidValue(tk.just_span(), "a struct field"));
+			}
+		}
+
 		s.size = Some(s.members.iter().map(|(_, tk)| tk.size()).sum());
 
 		self.program_data
@@ -110,167 +737,257 @@ impl<'a> Visitor for ResolveTypes<'a> {
 	}
 
 	fn function_decl(&mut self, func: &mut FunctionDeclaration) -> VResult {
-		self.current_scope = Some(func.ident.item.clone());
+		let param_types = func.params.iter().map(|x| x.0.item.clone()).collect::<Vec<_>>();
+		let key = signature(&func.ident.item, &param_types);
+		self.current_scope = Some(key.clone());
 
 		let mut fnc = FuncMeta::new();
 		fnc.return_type = Some(func.ret_type.item.clone());
-		let mut param_offset = 0;
+		fnc.param_types = param_types;
+		self.program_data.functions.insert(key, fnc);
+
+		self.declarations = vec![HashMap::new()];
+		self.out_of_scope.clear();
 
+		let mut param_offset = 0;
 		for (tk, ident) in &func.params {
-			fnc.symbols.insert(
-				ident.item.clone(),
-				SymbolMeta {
-					type_kind: tk.item.clone(),
-					is_static: false,
-					is_mutable: false,
-					stack_offset: Some(param_offset),
-				},
-			);
+			let symbol = SymbolMeta {
+				type_kind: tk.item.clone(),
+				is_static: false,
+				is_mutable: false,
+				stack_offset: Some(param_offset),
+			};
+			self.declare(ident, symbol, "parameter");
 
 			param_offset += tk.size();
 		}
-		fnc.param_types = func.params.iter().map(|x| x.0.item.clone()).collect();
-
-		self.program_data.functions.insert(func.ident.item.clone(), fnc);
 
 		Ok(())
 	}
 
 	fn pre_statement(&mut self, stmt: &mut Statement) -> VResult {
 		match stmt {
-			Statement::Loop(ident, _, _, _) => {
-				let scope = self.current_scope();
-
-				scope.symbols.insert(
-					ident.item.clone(),
-					SymbolMeta {
-						type_kind: TypeKind::I32,
-						is_static: false,
-						is_mutable: false,
-						stack_offset: None,
-					},
-				);
-			}
+			Statement::Loop(_) | Statement::While(_, _) => self.loop_depth += 1,
 			_ => {}
 		}
 
 		Ok(())
 	}
 
+	fn pre_loop_body(&mut self, l: &mut ForLoop) -> VResult {
+		// the loop variable lives in a scope around the body
+		self.push_scope();
+
+		let symbol = SymbolMeta {
+			type_kind: typekind_of(&l.from),
+			is_static: false,
+			is_mutable: false,
+			stack_offset: None,
+		};
+		self.declare(&l.ident, symbol, "variable");
+
+		Ok(())
+	}
+
 	fn post_statement(&mut self, stmt: &mut Statement) -> VResult {
 		match stmt {
 			Statement::VariableDeclaration(is_mut, ident, rhs) => {
-				let scope = self.current_scope();
-
-				scope.symbols.insert(
-					ident.item.clone(),
-					SymbolMeta {
-						type_kind: rhs
-							.typekind()
-							.expect(&format!("Expected expr {:#?} to be typed by this point.", rhs)),
-						is_static: false,
-						is_mutable: *is_mut,
-						stack_offset: None,
-					},
-				);
-			}
-			Statement::Assignment(ident, rhs) => {
-				let scope = self.current_scope();
-
-				if let Some(s) = scope.symbols.get(&ident.item) {
-					if !s.is_mutable {
-						Err(Box::new(TypeError::AssignmentToImmutable(ident.clone())))?;
+				if is_opaque(&typekind_of(rhs)) {
+					self.error(TypeError::OpaqueType(rhs.span(), typekind_of(rhs), "a variable"));
+				}
+				if is_void(&typekind_of(rhs)) {
+					self.error(TypeError::VoidValue(rhs.span(), "a variable"));
+				}
+
+				let symbol = SymbolMeta {
+					type_kind: typekind_of(rhs),
+					is_static: false,
+					is_mutable: *is_mut,
+					stack_offset: None,
+				};
+				self.declare(ident, symbol, "variable");
+			}
+			Statement::Assignment(place, rhs) => {
+				// the root symbol has already been resolved, so unknown symbols were reported by the visitor
+				let root = place.place_root().map(|s| s.raw.clone());
+				let meta = root.as_ref().and_then(|root| {
+					let local = self.current_scope().lookup(&root.item).cloned();
+					local.or_else(|| self.program_data.global_symbols.get(&root.item).cloned())
+				});
+
+				if let (Some(root), Some(meta)) = (root, meta) {
+					if !meta.is_mutable {
+						self.error(TypeError::AssignmentToImmutable(root));
 					}
+				}
 
-					let rhs_t = rhs.expect_typekind();
-					if s.type_kind != rhs_t {
-						Err(Box::new(TypeError::TypeError(
-							ident.clone(),
-							s.type_kind.clone(),
-							rhs_t,
-						)))?;
+				let mut target = Some(&*place);
+				while let Some(Expr::FieldAccess(e, f, _, None)) = target {
+					if matches!(typekind_of(e), TypeKind::Vector(_, _)) && has_duplicate_components(f) {
+						self.error(TypeError::DuplicateSwizzleComponent(f.clone()));
 					}
-				} else {
-					Err(Box::new(TypeError::UnknownSymbol(ident.clone())))?;
+					target = Some(&**e);
 				}
+
+				self.expect_type(rhs, &typekind_of(place));
 			}
-			Statement::Return(_, rhs) => {
-				let scope = self.current_scope();
+			Statement::Return(span, rhs) => {
+				let return_type = self.current_scope().return_type.clone().unwrap();
 
-				if &rhs.typekind() != &scope.return_type {
-					return Err(Box::new(TypeError::TypeError(
-						rhs.span()
-							.map(|_| String::from("Return type does not match type of return expression")),
-						rhs.expect_typekind(),
-						scope.return_type.clone().unwrap(),
-					)));
+				match rhs {
+					Some(rhs) if is_void(&typekind_of(rhs)) => {
+						self.error(TypeError::VoidValue(rhs.span(), "a return value"));
+					}
+					Some(rhs) => self.expect_type(rhs, &return_type),
+					None if !return_type.is_error() && return_type != TypeKind::Void => {
+						self.error(TypeError::TypeMismatch(*span, return_type, TypeKind::Void));
+					}
+					None => {}
 				}
 			}
 			Statement::Conditional(conditional) => {
-				let c = conditional.cond.as_ref().unwrap();
-				match c.expect_typekind() {
-					TypeKind::I32 | TypeKind::F32 => {}
-					t => {
-						Err(Box::new(TypeError::TypeError(
-							c.span().map(|_| String::from("ur bad")),
-							TypeKind::F32,
-							t,
-						)))?;
+				let mut branch = Some(&*conditional);
+				while let Some(b) = branch {
+					if let Some(c) = &b.cond {
+						self.expect_type(c, &TypeKind::Bool);
 					}
+					branch = b.alternate.as_deref();
 				}
 			}
-			Statement::Loop(_, from, to, _) => {
-				let l = from.expect_typekind();
-				if l != TypeKind::I32 {
-					Err(Box::new(TypeError::TypeError(
-						from.span().map(|_| String::from("ur bad")),
-						TypeKind::I32,
-						l,
-					)))?
+			Statement::Loop(l) => {
+				let counter = typekind_of(&l.from);
+				match counter {
+					TypeKind::I32 | TypeKind::U32 | TypeKind::F32 | TypeKind::Error => {}
+					_ => self.error(TypeError::TypeMismatch(l.from.span(), TypeKind::I32, counter.clone())),
 				}
-				let r = to.expect_typekind();
-				if r != TypeKind::I32 {
-					Err(Box::new(TypeError::TypeError(
-						to.span().map(|_| String::from("ur bad")),
-						TypeKind::I32,
-						r,
-					)))?
+				self.expect_type(&l.to, &counter);
+
+				if let Some(step) = &l.step {
+					self.expect_type(step, &counter);
+					if !typekind_of(step).is_error() && constant_step(step).filter(|s| *s != 0.0).is_none() {
+						self.error(TypeError::InvalidLoopStep(step.span()));
+					}
 				}
+
+				self.pop_scope();
+				self.loop_depth -= 1;
 			}
+			Statement::While(cond, _) => {
+				self.expect_type(cond, &TypeKind::Bool);
+				self.loop_depth -= 1;
+			}
+			Statement::Break(span) if self.loop_depth == 0 => {
+				self.error(TypeError::JumpOutsideLoop(*span, "break"));
+			}
+			Statement::Continue(span) if self.loop_depth == 0 => {
+				self.error(TypeError::JumpOutsideLoop(*span, "continue"));
+			}
+			Statement::Break(_) | Statement::Continue(_) | Statement::Expression(_) => {}
 		}
 
 		Ok(())
 	}
 
+	fn pre_block(&mut self) -> VResult {
+		self.push_scope();
+		Ok(())
+	}
+
+	fn post_block(&mut self) -> VResult {
+		self.pop_scope();
+		Ok(())
+	}
+
 	fn post_func_call(&mut self, func: &mut FuncCall) -> VResult {
-		let arg_types = func.1.iter().map(|e| e.typekind().unwrap()).collect::<Vec<_>>();
-
-		if let Some((_, builtin)) = crate::builtins::get_builtin_fn(func.0.raw.as_ref(), &arg_types) {
-			func.0.resolved = Some((func.0.raw.item.clone(), builtin.return_type()));
-			Ok(())
-		} else if let Some(f) = self.program_data.functions.get(func.0.raw.as_str()) {
-			// TODO: Implement function overloading in user land
-			if false
-				== arg_types
-					.iter()
-					.zip(f.param_types.iter())
-					.fold(true, |acc, (a, b)| acc && a == b)
-			{
-				Err(Box::new(TypeError::UnknownFunction(func.0.raw.clone(), arg_types)))?
-			}
-
-			func.0.resolved = Some((func.0.raw.item.clone(), f.return_type.clone().unwrap()));
-			Ok(())
-		} else {
-			Err(Box::new(TypeError::UnknownFunction(func.0.raw.clone(), arg_types)))
+		let arg_types = func.1.iter().map(|e| typekind_of(e)).collect::<Vec<_>>();
+
+		// an argument already failed to type check, don't cascade
+		if arg_types.iter().any(TypeKind::is_error) {
+			func.0.resolved = Some((func.0.raw.item.clone(), TypeKind::Error));
+			return Ok(());
+		}
+
+		if super::array_length(func).is_some() {
+			func.0.resolved = Some((func.0.raw.item.clone(), TypeKind::I32));
+			return Ok(());
+		}
+
+		if super::is_aggregate_comparison(func) {
+			func.0.resolved = Some((func.0.raw.item.clone(), TypeKind::Bool));
+			return Ok(());
+		}
+
+		let name = func.0.raw.as_str();
+		let builtin = crate::builtins::get_builtin_fn(name, &arg_types);
+		let key = signature(name, &arg_types);
+		let user = self.program_data.functions.get(&key);
+
+		// without an exact match, try converting the arguments
+		if builtin.is_none() && user.is_none() {
+			let mut candidates = self.conversion_candidates(name, &arg_types);
+
+			if candidates.len() == 1 {
+				let candidate = candidates.remove(0);
+				let args = std::mem::take(&mut func.1);
+				func.1 = args
+					.into_iter()
+					.zip(candidate.conversions)
+					.map(|(arg, steps)| steps.into_iter().fold(arg, |e, tk| Box::new(Expr::Conversion(e, tk))))
+					.collect();
+
+				// user defined functions resolve to their signature
+				if candidate.resolved != name {
+					self.record_call(&candidate.resolved, &func.0.raw);
+				}
+				func.0.resolved = Some((candidate.resolved, candidate.return_type));
+				return Ok(());
+			} else if candidates.len() > 1 {
+				// the candidates come from a `HashMap`, sort them so the error is the same every time
+				candidates.sort_by(|a, b| a.signature.cmp(&b.signature));
+				let candidates = candidates.into_iter().map(|c| c.description).collect();
+				self.error(TypeError::AmbiguousCall(func.0.raw.clone(), candidates));
+				func.0.resolved = Some((name.to_owned(), TypeKind::Error));
+				return Ok(());
+			}
 		}
+
+		func.0.resolved = Some(match (builtin, user) {
+			(Some((_, builtin)), None) => (name.to_owned(), builtin.return_type()),
+			// user defined functions are resolved to their signature, so codegen can tell overloads apart
+			(None, Some(f)) => {
+				let return_type = f.return_type.clone().unwrap();
+				self.record_call(&key, &func.0.raw);
+
+				(key, return_type)
+			}
+			(Some(_), Some(_)) => {
+				self.error(TypeError::AmbiguousCall(
+					func.0.raw.clone(),
+					vec![format!("builtin `{}`", key), format!("`{}`", key)],
+				));
+				(name.to_owned(), TypeKind::Error)
+			}
+			(None, None) => {
+				let mut arities = self.arities(name);
+				if !arities.is_empty() && !arities.contains(&arg_types.len()) {
+					arities.sort();
+					arities.dedup();
+					self.error(TypeError::WrongArgumentCount(func.0.raw.clone(), arities, arg_types.len()));
+				} else {
+					self.error(TypeError::UnknownFunction(func.0.raw.clone(), arg_types));
+				}
+				(name.to_owned(), TypeKind::Error)
+			}
+		});
+
+		Ok(())
 	}
 
 	fn post_expr(&mut self, e: &mut Expr) -> VResult {
 		match e {
-			Expr::FieldAccess(s, f, t, so) => match &s.resolved.as_ref().unwrap().1 {
-				TypeKind::Struct(s) => {
-					let s = s.borrow();
+			Expr::FieldAccess(e, f, t, so) => match typekind_of(e) {
+				TypeKind::Struct(decl) => {
+					let s = decl.borrow();
 					if let Some(field) = s.members.iter().find(|(mn, _)| &mn.item == &f.item) {
 						*t = Some(field.1.item.clone());
 						*so = Some(
@@ -280,56 +997,77 @@ impl<'a> Visitor for ResolveTypes<'a> {
 								.sum(),
 						);
 					} else {
-						Err(Box::new(TypeError::GenericError(format!(
-							"Field {:?} does not exist on struct {:?}",
-							f, s.ident
-						))))?;
+						self.error(TypeError::UnknownField(f.clone(), TypeKind::Struct(decl.clone())));
+						*t = Some(TypeKind::Error);
+					}
+				}
+				TypeKind::Vector(tk, n) => {
+					if swizzle_components(f, n).is_some() {
+						if f.len() == 1 {
+							*t = Some(*tk);
+						} else {
+							*t = Some(TypeKind::Vector(tk, f.len()));
+						}
+						*so = None
+					} else {
+						self.error(TypeError::InvalidSwizzle(f.clone()));
+						*t = Some(TypeKind::Error);
 					}
-                }
-                TypeKind::Vector(tk, _) => {
-                    fn all_in_set(s: &String, set: Vec<char>) -> bool {
-                        for c in s.chars() {
-                            if !set.contains(&c) {
-                                return false;
-                            }
-                        }
-                        true
-                    }
-
-                    let len = f.len();
-                    if len > 4 {
-                        Err(Box::new(TypeError::GenericError(format!(
-							"{:?} is not a valid vector accessor",
-							f
-						))))?;
-                    } else {
-                        if all_in_set(f, vec!['x', 'y', 'z', 'w']) || all_in_set(f, vec!['r', 'g', 'b', 'a']) {
-                            if len == 1 {
-                                *t = Some(*tk.clone());
-                            } else {
-                                *t = Some(TypeKind::Vector(tk.clone(), len));
-                            }
-                            *so = None
-                        } else {
-                            Err(Box::new(TypeError::GenericError(
-                                "Vector components are not from the same set".to_owned()
-                            )))?;
-                        }
-                    }
-                }
-				_ => Err(Box::new(TypeError::GenericError(format!(
-					"Field access on type {:?} is not valid",
-					s.resolved.as_ref().unwrap().1.clone()
-				))))?,
+				}
+				TypeKind::Error => *t = Some(TypeKind::Error),
+				tk => {
+					self.error(TypeError::InvalidFieldAccess(f.clone(), tk));
+					*t = Some(TypeKind::Error);
+				}
 			},
+			Expr::Index(e, index, brackets, t) => {
+				match typekind_of(index) {
+					TypeKind::I32 | TypeKind::U32 | TypeKind::Error => {}
+					found => self.error(TypeError::TypeMismatch(index.span(), TypeKind::I32, found)),
+				}
+
+				*t = Some(match typekind_of(e) {
+					TypeKind::Array(element, len) => {
+						if let Some(i) = constant_index(index).filter(|i| *i < 0 || *i as usize >= len) {
+							self.error(TypeError::IndexOutOfBounds(*brackets, i, len));
+						}
+						*element
+					}
+					TypeKind::Error => TypeKind::Error,
+					tk => {
+						self.error(TypeError::NotIndexable(e.span(), tk));
+						TypeKind::Error
+					}
+				});
+			}
+			Expr::ArrayLiteral(_, elements) => {
+				let element = typekind_of(&elements[0]);
+				for e in elements.iter().skip(1) {
+					self.expect_type(e, &element);
+				}
+			}
 			Expr::FuncCall(_) => {}
 			Expr::Grouped(_) => {}
+			Expr::Conversion(_, _) => {}
 			Expr::Literal(_) => {}
-			Expr::StructConstruction(name, s, _) => {
-				if let Some(newt) = self.program_data.struct_declarations.get(&name.item) {
-					*s = Some(newt.clone());
+			Expr::StructConstruction(name, s, fields) => {
+				if let Some(newt) = self.program_data.struct_declarations.get(&name.item).cloned() {
+					let decl = newt.borrow();
+					for (f, expr) in fields.iter() {
+						match decl.members.iter().find(|(mn, _)| mn.item == f.item) {
+							Some((_, tk)) => self.expect_type(expr, &tk.item),
+							None => self.error(TypeError::UnknownField(f.clone(), TypeKind::Struct(newt.clone()))),
+						}
+					}
+					for (member, _) in decl.members.iter() {
+						if !fields.iter().any(|(f, _)| f.item == member.item) {
+							self.error(TypeError::MissingField(name.clone(), member.item.clone()));
+						}
+					}
+					drop(decl);
+					*s = Some(newt);
 				} else {
-					Err(TypeError::UnknownType(name.clone()))?;
+					self.error(TypeError::UnknownType(name.clone()));
 				}
 			}
 			Expr::Symbol(_) => {}
@@ -339,6 +1077,14 @@ impl<'a> Visitor for ResolveTypes<'a> {
 	}
 
 	fn post_in_parameter(&mut self, param: &mut InParameterDeclaration) -> VResult {
+		if is_void(&param.type_kind.item) {
+			self.error(TypeError::VoidValue(param.type_kind.just_span(), "an input"));
+		}
+		if !param.is_uniform && is_opaque(&param.type_kind.item) {
+			let tk = &param.type_kind;
+			self.error(TypeError::OpaqueType(tk.just_span(), tk.item.clone(), "a non-uniform input"));
+		}
+
 		self.program_data.global_symbols.insert(
 			param.ident.item.clone(),
 			SymbolMeta {
@@ -353,7 +1099,174 @@ impl<'a> Visitor for ResolveTypes<'a> {
 	}
 }
 
-pub fn resolve<'a>(ast: &'a mut Program, data: &'a mut ProgramData) -> VResult {
+/// The name of the struct that a member type refers to, if any.
+fn struct_ref(tk: &TypeKind) -> Option<&Ident> {
+	match tk {
+		TypeKind::TypeRef(name) => Some(&name.item),
+		TypeKind::Array(element, _) => struct_ref(element),
+		_ => None,
+	}
+}
+
+/// Sorts struct declarations so that every struct comes after the structs its fields use, which have to be resolved
+/// first to know their sizes.
+///
+/// Structs that contain themselves, directly or through other structs, would have an infinite size. The fields that
+/// close such a cycle are reported and given the type `TypeKind::Error`.
+fn sort_structs(structs: &mut Vec<StructDeclaration>) -> Vec<TypeError> {
+	#[derive(Clone, PartialEq)]
+	enum State {
+		Unvisited,
+		InProgress,
+		Done,
+	}
+
+	fn visit(
+		i: usize,
+		structs: &[StructDeclaration],
+		index: &HashMap<&Ident, usize>,
+		state: &mut Vec<State>,
+		order: &mut Vec<usize>,
+		recursive: &mut Vec<(usize, usize)>,
+	) {
+		state[i] = State::InProgress;
+		for (m, (_, tk)) in structs[i].members.iter().enumerate() {
+			if let Some(&dependency) = struct_ref(&tk.item).and_then(|name| index.get(name)) {
+				match state[dependency] {
+					State::Unvisited => visit(dependency, structs, index, state, order, recursive),
+					State::InProgress => recursive.push((i, m)),
+					State::Done => {}
+				}
+			}
+		}
+		state[i] = State::Done;
+		order.push(i);
+	}
+
+	let index = structs
+		.iter()
+		.enumerate()
+		.rev()
+		.map(|(i, s)| (&s.ident.item, i))
+		.collect::<HashMap<_, _>>();
+	let mut state = vec![State::Unvisited; structs.len()];
+	let mut order = Vec::new();
+	let mut recursive = Vec::new();
+	for i in 0..structs.len() {
+		if state[i] == State::Unvisited {
+			visit(i, structs, &index, &mut state, &mut order, &mut recursive);
+		}
+	}
+
+	let mut errors = Vec::new();
+	for (i, m) in recursive {
+		let s = &mut structs[i];
+		errors.push(TypeError::RecursiveStruct(s.ident.clone(), s.members[m].1.just_span()));
+		s.members[m].1.item = TypeKind::Error;
+	}
+
+	let mut slots = structs.drain(..).map(Some).collect::<Vec<_>>();
+	*structs = order.into_iter().map(|i| slots[i].take().unwrap()).collect();
+
+	errors
+}
+
+/// Checks that every field of a struct returned from `main` can be a shader output.
+fn invalid_outputs(ast: &Program) -> Vec<TypeError> {
+	fn is_output_type(tk: &TypeKind) -> bool {
+		match tk {
+			TypeKind::F32 | TypeKind::I32 | TypeKind::U32 => true,
+			TypeKind::Vector(element, _) => is_output_type(element),
+			TypeKind::Array(element, _) => !matches!(**element, TypeKind::Array(_, _)) && is_output_type(element),
+			_ => false,
+		}
+	}
+
+	let ret_type = match ast.functions.iter().find(|f| f.ident.item == "main" && f.params.is_empty()) {
+		Some(main) => &main.ret_type,
+		None => return vec![],
+	};
+
+	match &ret_type.item {
+		TypeKind::Struct(s) => s
+			.borrow()
+			.members
+			.iter()
+			.filter(|(_, tk)| !is_output_type(&tk.item))
+			.map(|(_, tk)| TypeError::InvalidOutput(tk.just_span(), tk.item.clone(), ret_type.just_span()))
+			.collect(),
+		_ => vec![],
+	}
+}
+
+/// Finds top level items and struct members which are defined more than once. Functions are only duplicates if their
+/// signatures are the same, otherwise they are overloads.
+fn duplicate_definitions(ast: &Program) -> Vec<TypeError> {
+	let mut errors = Vec::new();
+	let mut check = |seen: &mut HashMap<String, Spanned<()>>, key: String, ident: &Spanned<Ident>, kind| {
+		match seen.get(&key) {
+			Some(previous) => errors.push(TypeError::Redefinition(ident.clone(), *previous, kind)),
+			None => {
+				seen.insert(key, ident.just_span());
+			}
+		}
+	};
+
+	let mut structs = HashMap::new();
+	for s in ast.struct_declarations.iter() {
+		check(&mut structs, s.ident.item.clone(), &s.ident, "struct");
+
+		let mut members = HashMap::new();
+		for (member, _) in s.members.iter() {
+			check(&mut members, member.item.clone(), member, "field");
+		}
+	}
+
+	let mut globals = HashMap::new();
+	for p in ast.in_parameters.iter() {
+		check(&mut globals, p.ident.item.clone(), &p.ident, "global");
+	}
+
+	let mut functions = HashMap::new();
+	for f in ast.functions.iter() {
+		let param_types = f.params.iter().map(|(tk, _)| tk.item.clone()).collect::<Vec<_>>();
+		check(&mut functions, signature(&f.ident.item, &param_types), &f.ident, "function");
+	}
+
+	errors
+}
+
+/// Resolves the types of every expression in the program, returning all type errors that were found.
+///
+/// Expressions that fail to type check are given the type `TypeKind::Error`, which suppresses follow-up errors in
+/// the expressions containing them. Structs and functions are reordered so that every item comes after the items it
+/// uses.
+pub fn resolve<'a>(ast: &'a mut Program, data: &'a mut ProgramData) -> Result<(), Vec<TypeError>> {
 	let mut rt = ResolveTypes::new(data);
-	ast.visit(&mut rt)
+	for e in duplicate_definitions(ast) {
+		rt.error(e);
+	}
+
+	(|| {
+		rt.declare_items(ast)?;
+		ast.in_parameters.visit(&mut rt)?;
+		ast.functions.visit(&mut rt)
+	})()
+	.expect("ResolveTypes records errors instead of aborting the visit");
+	rt.sort_functions(&mut ast.functions);
+
+	let mut errors = Vec::new();
+	for func in ast.functions.iter() {
+		control_flow::check_function(func, &mut errors, &mut rt.program_data.warnings);
+	}
+	errors.extend(invalid_outputs(ast));
+	for e in errors {
+		rt.error(e);
+	}
+
+	if rt.errors.is_empty() {
+		Ok(())
+	} else {
+		Err(rt.errors)
+	}
 }
@@ -0,0 +1,202 @@
+use crate::ast::Spanned;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Severity {
+	Error,
+	Warning,
+	Note,
+}
+
+impl fmt::Display for Severity {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Severity::Error => write!(f, "error"),
+			Severity::Warning => write!(f, "warning"),
+			Severity::Note => write!(f, "note"),
+		}
+	}
+}
+
+#[derive(Debug, Clone)]
+pub struct Label {
+	pub span: Spanned<()>,
+	pub message: Option<String>,
+}
+
+/// A message about the source code, produced by any stage of the compiler.
+///
+/// Diagnostics point into the source with one primary label (the thing that is wrong) and any number of secondary
+/// labels (context, e.g. a previous declaration). Use `render` to print them together with the offending lines.
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+	pub severity: Severity,
+	pub code: Option<&'static str>,
+	pub message: String,
+	pub primary: Option<Label>,
+	pub secondary: Vec<Label>,
+	pub notes: Vec<String>,
+}
+
+impl Diagnostic {
+	pub fn new(severity: Severity, message: impl Into<String>) -> Self {
+		Diagnostic {
+			severity,
+			code: None,
+			message: message.into(),
+			primary: None,
+			secondary: Vec::new(),
+			notes: Vec::new(),
+		}
+	}
+
+	pub fn error(message: impl Into<String>) -> Self {
+		Self::new(Severity::Error, message)
+	}
+
+	pub fn warning(message: impl Into<String>) -> Self {
+		Self::new(Severity::Warning, message)
+	}
+
+	pub fn with_code(mut self, code: &'static str) -> Self {
+		self.code = Some(code);
+		self
+	}
+
+	pub fn with_primary(mut self, span: Spanned<()>, message: impl Into<String>) -> Self {
+		self.primary = Some(Label {
+			span,
+			message: Some(message.into()),
+		});
+		self
+	}
+
+	pub fn with_primary_span(mut self, span: Spanned<()>) -> Self {
+		self.primary = Some(Label { span, message: None });
+		self
+	}
+
+	pub fn with_secondary(mut self, span: Spanned<()>, message: impl Into<String>) -> Self {
+		self.secondary.push(Label {
+			span,
+			message: Some(message.into()),
+		});
+		self
+	}
+
+	pub fn with_note(mut self, note: impl Into<String>) -> Self {
+		self.notes.push(note.into());
+		self
+	}
+
+	pub fn is_error(&self) -> bool {
+		self.severity == Severity::Error
+	}
+
+	/// Renders the diagnostic together with the source lines it refers to, e.g.
+	///
+	/// ```text
+	/// error[E0301]: unknown symbol `foo`
+	///  --> 3:9
+	///   |
+	/// 3 |     return foo
+	///   |            ^^^ not found in this scope
+	/// ```
+	pub fn render(&self, source: &str) -> String {
+		let lines = source.lines().collect::<Vec<_>>();
+
+		let mut labels = Vec::new();
+		if let Some(primary) = &self.primary {
+			labels.push((primary, '^'));
+		}
+		labels.extend(self.secondary.iter().map(|l| (l, '-')));
+
+		let gutter = labels
+			.iter()
+			.map(|(l, _)| l.span.from.line.to_string().len())
+			.max()
+			.unwrap_or(1);
+		let pad = " ".repeat(gutter);
+
+		let mut output = self.header();
+		output.push('\n');
+
+		if let Some(primary) = &self.primary {
+			output.push_str(&format!("{}--> {}\n", pad, primary.span.from));
+		}
+
+		let mut by_line = labels.clone();
+		by_line.sort_by_key(|(l, _)| l.span.from.line);
+
+		let mut last_line = None;
+		for (label, marker) in by_line {
+			let line_no = label.span.from.line;
+			let text = match lines.get((line_no as usize).wrapping_sub(1)) {
+				Some(text) => *text,
+				None => continue,
+			};
+
+			if last_line != Some(line_no) {
+				output.push_str(&format!("{} |\n", pad));
+				output.push_str(&format!("{:>w$} | {}\n", line_no, text, w = gutter));
+				last_line = Some(line_no);
+			}
+
+			// tabs are kept as is so the markers line up with the source line above
+			let start = label.span.from.column.saturating_sub(1) as usize;
+			let end = if label.span.to.line == line_no {
+				label.span.to.column.saturating_sub(1) as usize
+			} else {
+				text.chars().count()
+			};
+			let indent = text
+				.chars()
+				.take(start)
+				.map(|c| if c == '\t' { '\t' } else { ' ' })
+				.collect::<String>();
+			let markers = marker.to_string().repeat(end.saturating_sub(start).max(1));
+
+			output.push_str(&format!("{} | {}{}", pad, indent, markers));
+			if let Some(message) = &label.message {
+				output.push(' ');
+				output.push_str(message);
+			}
+			output.push('\n');
+		}
+
+		if !self.notes.is_empty() {
+			output.push_str(&format!("{} |\n", pad));
+		}
+		for note in self.notes.iter() {
+			output.push_str(&format!("{} = note: {}\n", pad, note));
+		}
+
+		output
+	}
+
+	fn header(&self) -> String {
+		match self.code {
+			Some(code) => format!("{}[{}]: {}", self.severity, code, self.message),
+			None => format!("{}: {}", self.severity, self.message),
+		}
+	}
+}
+
+impl fmt::Display for Diagnostic {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{}", self.header())?;
+		if let Some(primary) = &self.primary {
+			write!(f, " at {}", primary.span.from)?;
+		}
+		Ok(())
+	}
+}
+
+/// Renders a list of diagnostics, separated by blank lines.
+pub fn render_all<'a>(diagnostics: impl IntoIterator<Item = &'a Diagnostic>, source: &str) -> String {
+	diagnostics
+		.into_iter()
+		.map(|d| d.render(source))
+		.collect::<Vec<_>>()
+		.join("\n")
+}
@@ -1,4 +1,5 @@
 use super::*;
+use std::collections::HashSet;
 
 #[derive(Debug)]
 pub struct GenerateGLSL {
@@ -6,6 +7,8 @@ pub struct GenerateGLSL {
 	pub current_fn: Option<String>,
 	pub prelude: String,
 	pub indent: usize,
+	/// Names of user defined functions with more than one overload, which are mangled in the output.
+	pub overloaded: HashSet<String>,
 }
 impl GenerateGLSL {
 	pub fn new() -> Self {
@@ -14,6 +17,7 @@ impl GenerateGLSL {
 			current_fn: None,
 			prelude: String::new(),
 			indent: 0,
+			overloaded: HashSet::new(),
 		}
 	}
 
@@ -41,6 +45,13 @@ impl GenerateGLSL {
 			self.consume_struct_decl(i);
 		}
 
+		let mut names = HashSet::new();
+		for f in program.functions.iter() {
+			if !names.insert(&f.ident.item) {
+				self.overloaded.insert(f.ident.item.clone());
+			}
+		}
+
 		for i in program.functions.iter() {
 			self.consume_func_decl(i);
 		}
@@ -56,6 +67,11 @@ impl GenerateGLSL {
 			false => "in"
 		};
 
+		if let Some(doc) = &param.doc {
+			for line in doc.lines() {
+				self.prelude.push_str(&format!("// {}\n", line));
+			}
+		}
 		self.prelude
 			.push_str(&format!("{} {} {};\n", param_type, glsl_type, param.ident.item));
 	}
@@ -71,10 +87,8 @@ impl GenerateGLSL {
 	pub fn consume_func_decl(&mut self, decl: &FunctionDeclaration) {
 		let glsl_type = get_glsl_type(&decl.ret_type);
 
-		let func_ident = match decl.ident.item.as_str() {
-			"main" => "m_impl_main".to_owned(),
-			i => i.to_owned(),
-		};
+		let param_types = decl.params.iter().map(|(tk, _)| tk.item.clone()).collect::<Vec<_>>();
+		let func_ident = self.function_name(&decl.ident.item, &param_types);
 
 		let func_body = self.generate_statements(&decl.statements);
 
@@ -90,16 +104,56 @@ impl GenerateGLSL {
 		self.functions.push((func_ident, func_text));
 	}
 
+	/// The name of a user defined function in the output, with the parameter types appended if it is overloaded.
+	pub fn function_name(&self, ident: &str, param_types: &[TypeKind]) -> String {
+		if ident == "main" && param_types.is_empty() {
+			return "m_impl_main".to_owned();
+		}
+		if !self.overloaded.contains(ident) {
+			return ident.to_owned();
+		}
+
+		let types = param_types.iter().map(mangle);
+		std::iter::once(ident.to_owned()).chain(types).collect::<Vec<_>>().join("_")
+	}
+
 	pub fn generate_main_shim(&mut self, main: &FunctionDeclaration) {
-		// todo implement structs
+		// a `Void` main has no outputs
+		if let TypeKind::Void = main.ret_type.item {
+			let shim_text = "void main() {\n\tm_impl_main();\n}\n".to_owned();
+			self.functions.push(("main".to_owned(), shim_text));
+			return;
+		}
+
 		let glsl_type = get_glsl_type(&main.ret_type.item);
 
-		self.prelude.push_str(&format!("out {} {};\n", glsl_type, "out_0"));
+		// each field of a returned struct becomes its own output
+		let assignments = match &main.ret_type.item {
+			TypeKind::Struct(s) => {
+				let mut location = 0;
+				let mut assignments = String::new();
+
+				for (f, tk) in s.borrow().members.iter() {
+					self.prelude.push_str(&format!(
+						"layout(location = {}) out {} out_{};\n",
+						location,
+						get_glsl_type(tk),
+						f.item
+					));
+					assignments.push_str(&format!("\tout_{} = rt.{};\n", f.item, f.item));
+
+					location += output_locations(tk);
+				}
+
+				assignments
+			}
+			_ => {
+				self.prelude.push_str(&format!("out {} {};\n", glsl_type, "out_0"));
+				"\tout_0 = rt;\n".to_owned()
+			}
+		};
 
-		let shim_text = format!(
-			"void main() {{\n\t{} rt = m_impl_main();\n\tout_0 = rt;\n}}\n",
-			glsl_type
-		);
+		let shim_text = format!("void main() {{\n\t{} rt = m_impl_main();\n{}}}\n", glsl_type, assignments);
 
 		self.functions.push(("main".to_owned(), shim_text));
 	}
@@ -119,10 +173,17 @@ impl GenerateGLSL {
 						self.generate_expr(expr)
 					)
 				}
-				Statement::Assignment(id, expr) => {
-					format!("{}{} = {};", self.indent_string(), id.item, self.generate_expr(expr))
+				Statement::Assignment(place, expr) => format!(
+					"{}{} = {};",
+					self.indent_string(),
+					self.generate_expr(place),
+					self.generate_expr(expr)
+				),
+				Statement::Return(_, Some(expr)) => {
+					format!("{}return {};", self.indent_string(), self.generate_expr(expr))
 				}
-				Statement::Return(_, expr) => format!("{}return {};", self.indent_string(), self.generate_expr(expr)),
+				Statement::Return(_, None) => format!("{}return;", self.indent_string()),
+				Statement::Expression(expr) => format!("{}{};", self.indent_string(), self.generate_expr(expr)),
 				Statement::Conditional(conditional) => {
 					fn generate_conditional(this: &mut GenerateGLSL, c: &Conditional) -> String {
 						let mut result = String::new();
@@ -131,7 +192,7 @@ impl GenerateGLSL {
 						if let Some(cond) = &c.cond {
 							let expr = this.generate_expr(&cond);
 							result.extend(
-								format!("if (bool({})) {{\n{}\n{}}}", expr, stmts, this.indent_string()).chars(),
+								format!("if ({}) {{\n{}\n{}}}", expr, stmts, this.indent_string()).chars(),
 							);
 
 							if let Some(next) = &c.alternate {
@@ -147,20 +208,36 @@ impl GenerateGLSL {
 
 					format!("{}{}", self.indent_string(), generate_conditional(self, conditional))
 				}
-				Statement::Loop(ident, from, to, body) => {
-					let typekind = get_glsl_type(&from.typekind().unwrap());
+				Statement::Loop(l) => {
+					let typekind = get_glsl_type(&l.from.typekind().unwrap());
+					let step = l.step.as_ref().and_then(crate::compiler::resolve_types::constant_step).unwrap_or(1.0);
+					let increment = match &l.step {
+						Some(step) => format!("{} += {}", l.ident.item, self.generate_expr(step)),
+						None => format!("{}++", l.ident.item),
+					};
 					format!(
-						"{}for ({} {}={}; {} < {}; i++) {{\n{}\n{}}}",
+						"{}for ({} {} = {}; {} {} {}; {}) {{\n{}\n{}}}",
 						self.indent_string(),
 						typekind,
-						ident.item,
-						self.generate_expr(from),
-						ident.item,
-						self.generate_expr(to),
-						self.generate_statements(&body),
+						l.ident.item,
+						self.generate_expr(&l.from),
+						l.ident.item,
+						if step < 0.0 { ">" } else { "<" },
+						self.generate_expr(&l.to),
+						increment,
+						self.generate_statements(&l.body),
 						self.indent_string()
 					)
 				}
+				Statement::While(cond, body) => format!(
+					"{}while ({}) {{\n{}\n{}}}",
+					self.indent_string(),
+					self.generate_expr(cond),
+					self.generate_statements(body),
+					self.indent_string()
+				),
+				Statement::Break(_) => format!("{}break;", self.indent_string()),
+				Statement::Continue(_) => format!("{}continue;", self.indent_string()),
 			})
 			.collect::<Vec<String>>()
 			.join("\n");
@@ -172,6 +249,16 @@ impl GenerateGLSL {
 	pub fn generate_expr(&mut self, expr: &Expr) -> String {
 		match expr {
 			Expr::Symbol(s) => s.resolved.clone().unwrap().0,
+			Expr::FuncCall(func) if crate::compiler::array_length(func).is_some() => {
+				format!("{}.length()", self.generate_expr(&func.1[0]))
+			}
+			Expr::FuncCall(func) if crate::compiler::is_aggregate_comparison(func) => {
+				let op = match func.0.raw.as_str() {
+					"__op_binary_equality" => "==",
+					_ => "!=",
+				};
+				format!("{} {} {}", self.generate_expr(&func.1[0]), op, self.generate_expr(&func.1[1]))
+			}
 			Expr::FuncCall((f, args)) => {
 				let arg_types = args.iter().map(|e| e.typekind().unwrap()).collect::<Vec<_>>();
 
@@ -180,12 +267,25 @@ impl GenerateGLSL {
 				if let Some((_, builtin)) = crate::builtins::get_builtin_fn(f.raw.as_ref(), &arg_types) {
 					builtin.generate(self, args)
 				} else {
-					format!("{}({})", f.raw.clone().item, args.join(", "))
+					format!("{}({})", self.function_name(&f.raw.item, &arg_types), args.join(", "))
 				}
 			}
-			Expr::FieldAccess(s, f, _, _) => format!("{}.{}", s.resolved.clone().unwrap().0, f.item.clone()),
+			Expr::FieldAccess(e, f, _, _) => format!("{}.{}", self.generate_expr(e), f.item),
+			Expr::Index(e, index, _, _) => format!("{}[{}]", self.generate_expr(e), self.generate_expr(index)),
+			Expr::ArrayLiteral(_, elements) => {
+				let elements = elements.iter().map(|e| self.generate_expr(e)).collect::<Vec<_>>();
+
+				format!("{}({})", get_glsl_type(&expr.typekind().unwrap()), elements.join(", "))
+			}
 			Expr::Literal(l) => l.to_string(),
 			Expr::Grouped(e) => format!("({})", self.generate_expr(e)),
+			Expr::Conversion(e, tk) => {
+				let from = e.typekind().unwrap();
+				let arg = self.generate_expr(e);
+
+				let (_, conversion) = crate::builtins::get_builtin_fn(&tk.to_string(), &[from]).unwrap();
+				conversion.generate(self, vec![arg])
+			}
 			Expr::StructConstruction(name, s, fields) => {
 				let decl = s.as_ref().unwrap().borrow();
 
@@ -211,3 +311,20 @@ impl GenerateGLSL {
 		(0..self.indent).map(|_| "\t").collect()
 	}
 }
+
+/// The name of a type in mangled function names, e.g. `Float[3]` is `Float_arr3` so it doesn't clash with a struct
+/// named `Float3`.
+fn mangle(tk: &TypeKind) -> String {
+	match tk {
+		TypeKind::Array(element, n) => format!("{}_arr{}", mangle(element), n),
+		tk => tk.to_string().chars().filter(|c| c.is_ascii_alphanumeric()).collect(),
+	}
+}
+
+/// The number of locations an output of the type takes up, arrays take one per element.
+fn output_locations(tk: &TypeKind) -> usize {
+	match tk {
+		TypeKind::Array(element, n) => output_locations(element) * n,
+		_ => 1,
+	}
+}
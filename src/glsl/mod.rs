@@ -10,8 +10,15 @@ pub trait BuiltInCallableGLSL {
 fn get_glsl_type(tk: &TypeKind) -> String {
 	match tk {
 		TypeKind::F32 => "float".to_owned(),
+		TypeKind::Bool => "bool".to_owned(),
 		TypeKind::I32 => "int".to_owned(),
-		TypeKind::Vector(_, size) => format!("vec{}", size),
+		TypeKind::U32 => "uint".to_owned(),
+		TypeKind::Vector(element, size) => match **element {
+			TypeKind::I32 => format!("ivec{}", size),
+			TypeKind::U32 => format!("uvec{}", size),
+			TypeKind::Bool => format!("bvec{}", size),
+			_ => format!("vec{}", size),
+		},
 		TypeKind::Matrix(_, m, n) => {
 			if m == n {
 				format!("mat{}", m)
@@ -19,8 +26,10 @@ fn get_glsl_type(tk: &TypeKind) -> String {
 				format!("mat{}x{}", m, n)
 			}
 		}
+		TypeKind::Array(element, len) => format!("{}[{}]", get_glsl_type(element), len),
 		TypeKind::Void => "void".to_owned(),
 		TypeKind::Struct(s) => s.borrow().ident.item.clone(),
+		TypeKind::Sampler2D => "sampler2D".to_owned(),
 		t => {
 			dbg!(t);
 			unimplemented!()
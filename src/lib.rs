@@ -6,6 +6,7 @@
 pub mod ast;
 pub mod builtins;
 pub mod compiler;
+pub mod diagnostic;
 pub mod glsl;
 pub mod parser;
 pub mod scanner;
@@ -1,14 +1,41 @@
-use crate::ast::*;
+use crate::{ast::*, diagnostic::Diagnostic};
 
 use crate::scanner::*;
-use std::iter::{Iterator, Peekable};
+use std::{
+	fmt,
+	iter::{Iterator, Peekable},
+};
 
-pub fn parse(input: impl AsRef<str>) -> Result<Program, ParsingError> {
-	let tokens = Scanner::new(input.as_ref().chars()).scan_all()?;
+pub fn parse(input: impl AsRef<str>) -> Result<Program, Vec<ParsingError>> {
+	parse_source(input, SourceId::default())
+}
+
+/// Like `parse`, but tags every position in the program with `source`.
+pub fn parse_source(input: impl AsRef<str>, source: SourceId) -> Result<Program, Vec<ParsingError>> {
+	let (program, errors) = parse_recovering_source(input, source);
 
-	let mut tokens = tokens.into_iter().peekable();
+	if errors.is_empty() {
+		Ok(program)
+	} else {
+		Err(errors)
+	}
+}
 
-	parse_program(&mut tokens)
+/// Parses as much of the input as possible, returning the (possibly partial) program along with every error found.
+pub fn parse_recovering(input: impl AsRef<str>) -> (Program, Vec<ParsingError>) {
+	parse_recovering_source(input, SourceId::default())
+}
+
+pub fn parse_recovering_source(input: impl AsRef<str>, source: SourceId) -> (Program, Vec<ParsingError>) {
+	let (tokens, scanning_errors) = Scanner::with_source(input.as_ref().chars(), source).scan_all_recovering();
+
+	let mut tokens = strip_nested_doc_comments(tokens).into_iter().peekable();
+	let (program, parsing_errors) = parse_program(&mut tokens);
+
+	let mut errors = scanning_errors.into_iter().map(ParsingError::from).collect::<Vec<_>>();
+	errors.extend(parsing_errors);
+
+	(program, errors)
 }
 
 type ItemType = Spanned<Token>;
@@ -16,7 +43,11 @@ type ItemType = Spanned<Token>;
 #[derive(Debug, Clone)]
 pub enum ParsingError {
 	UnexpectedToken(ItemType),
+	ExpectedToken(Token, ItemType),
+	ExpectedIdentifier(ItemType),
 	UnexpectedEndOfInput,
+	/// A doc comment somewhere other than before a top level item, e.g. inside of a parameter list.
+	MisplacedDocComment(ItemType),
 	ScanningError(ScanningError),
 }
 
@@ -26,6 +57,66 @@ impl From<ScanningError> for ParsingError {
 	}
 }
 
+impl From<ParsingError> for Diagnostic {
+	fn from(e: ParsingError) -> Diagnostic {
+		match e {
+			ParsingError::UnexpectedToken(t) => Diagnostic::error(format!("unexpected token `{}`", t.item))
+				.with_code("E0201")
+				.with_primary_span(t.just_span()),
+			ParsingError::ExpectedToken(expected, t) => {
+				Diagnostic::error(format!("expected `{}`, found `{}`", expected, t.item))
+					.with_code("E0202")
+					.with_primary(t.just_span(), format!("expected `{}`", expected))
+			}
+			ParsingError::ExpectedIdentifier(t) => {
+				Diagnostic::error(format!("expected an identifier, found `{}`", t.item))
+					.with_code("E0203")
+					.with_primary(t.just_span(), "expected an identifier")
+			}
+			ParsingError::UnexpectedEndOfInput => Diagnostic::error("unexpected end of input").with_code("E0204"),
+			ParsingError::MisplacedDocComment(t) => Diagnostic::error("doc comment not allowed here")
+				.with_code("E0205")
+				.with_primary(t.just_span(), "doc comments must precede an input, struct or function")
+				.with_note("use `//` for a normal comment"),
+			ParsingError::ScanningError(e) => e.into(),
+		}
+	}
+}
+
+impl ParsingError {
+	pub fn span(&self) -> Option<Spanned<()>> {
+		match self {
+			ParsingError::UnexpectedToken(t) => Some(t.just_span()),
+			ParsingError::ExpectedToken(_, t) => Some(t.just_span()),
+			ParsingError::ExpectedIdentifier(t) => Some(t.just_span()),
+			ParsingError::UnexpectedEndOfInput => None,
+			ParsingError::MisplacedDocComment(t) => Some(t.just_span()),
+			ParsingError::ScanningError(ScanningError::UnexpectedCharacter(c)) => Some(c.just_span()),
+			ParsingError::ScanningError(ScanningError::InvalidLiteral(s)) => Some(*s),
+			ParsingError::ScanningError(ScanningError::UnterminatedBlockComment(s)) => Some(*s),
+			ParsingError::ScanningError(ScanningError::UnexpectedEndOfFile) => None,
+		}
+	}
+
+	/// Reports a doc comment the parser stumbled over as misplaced, instead of as an unexpected token.
+	fn misplaced_doc_comment(self) -> Self {
+		match self {
+			ParsingError::UnexpectedToken(t) | ParsingError::ExpectedToken(_, t) | ParsingError::ExpectedIdentifier(t)
+				if matches!(t.item, Token::DocComment(_)) =>
+			{
+				ParsingError::MisplacedDocComment(t)
+			}
+			e => e,
+		}
+	}
+}
+
+impl fmt::Display for ParsingError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{}", Diagnostic::from(self.clone()))
+	}
+}
+
 type ParsingResult<T> = Result<T, ParsingError>;
 
 pub trait TokenSource: std::clone::Clone {
@@ -40,17 +131,28 @@ pub trait TokenSource: std::clone::Clone {
 	}
 
 	fn expect_token(&mut self, token: Token) -> ParsingResult<ItemType> {
-		match self.expect_next()? {
-			t if *t == token => Ok(t),
-			t => Err(ParsingError::UnexpectedToken(t)),
+		// the offending token is left in place, so error recovery can synchronize on it
+		match TokenSource::peek(self) {
+			None => Err(ParsingError::UnexpectedEndOfInput),
+			Some(t) if t.item == token => self.expect_next(),
+			Some(t) => Err(ParsingError::ExpectedToken(token, t.clone())),
 		}
 	}
 
 	fn expect_identifier(&mut self) -> ParsingResult<Spanned<String>> {
-		let token = self.expect_next()?;
-		match token.item {
-			Token::Identifier(s) => Ok(Spanned::new(s, token.from, token.to)),
-			_ => Err(ParsingError::UnexpectedToken(token)),
+		match TokenSource::peek(self) {
+			None => Err(ParsingError::UnexpectedEndOfInput),
+			Some(Spanned {
+				item: Token::Identifier(_),
+				..
+			}) => {
+				let token = self.expect_next()?;
+				Ok(token.map(|t| match t {
+					Token::Identifier(s) => s.clone(),
+					_ => unreachable!(),
+				}))
+			}
+			Some(t) => Err(ParsingError::ExpectedIdentifier(t.clone())),
 		}
 	}
 
@@ -65,10 +167,29 @@ pub trait TokenSource: std::clone::Clone {
 			t => Err(ParsingError::UnexpectedToken(token.map(|_| t.clone()))),
 		});
 
-		match res.item {
-			Ok(t) => Ok(token.map(|_| t)),
-			Err(e) => Err(e),
+		let element = match res.item {
+			Ok(t) => token.map(|_| t),
+			Err(e) => return Err(e),
+		};
+
+		// arrays, e.g. `Float[8]`
+		if self.maybe_expect(Token::LeftBracket).is_none() {
+			return Ok(element);
 		}
+		let len = match self.expect_next()? {
+			Spanned {
+				item: Token::IntegerLiteral(n),
+				..
+			} if n > 0 => n as usize,
+			t => return Err(ParsingError::UnexpectedToken(t)),
+		};
+		let end = self.expect_token(Token::RightBracket)?;
+
+		Ok(Spanned::encompass(
+			TypeKind::Array(Box::new(element.item.clone()), len),
+			element,
+			end,
+		))
 	}
 
 	fn maybe_expect(&mut self, token: Token) -> Option<ItemType> {
@@ -92,178 +213,366 @@ where
 	}
 }
 
-pub fn parse_program(tokens: &mut impl TokenSource) -> ParsingResult<Program> {
+pub fn parse_program(tokens: &mut impl TokenSource) -> (Program, Vec<ParsingError>) {
 	let mut program = Program::new();
+	let mut errors = Vec::new();
 
-	'parsing: loop {
-		let token = tokens.peek();
-		if token.is_none() {
-			break 'parsing;
-		}
-		let token = token.unwrap();
+	while let Some(token) = tokens.peek() {
+		let token = token.clone();
 
-		match &token.item {
-			Token::In => {
-				tokens.expect_token(Token::In)?;
-				let type_kind = tokens.expect_typekind()?;
+		if let Err(e) = parse_top_level_item(tokens, &mut program, &mut errors) {
+			let e = e.misplaced_doc_comment();
 
-				let ident = tokens.expect_identifier()?;
-				program.in_parameters.push(InParameterDeclaration { type_kind, ident, is_uniform: false });
-				continue;
+			// the rest of the item is skipped, instead of parsing it as a new item documented by the comment
+			if let ParsingError::MisplacedDocComment(_) = e {
+				parse_doc_comments(tokens);
 			}
-			Token::Uniform => {
-				tokens.expect_token(Token::Uniform)?;
-				let type_kind = tokens.expect_typekind()?;
+			errors.push(e);
+			synchronize_top_level(tokens);
 
-				let ident = tokens.expect_identifier()?;
-				program.in_parameters.push(InParameterDeclaration { type_kind, ident, is_uniform: true });
-				continue;
+			// make sure we always make progress
+			if tokens.peek().map(|t| t.from) == Some(token.from) {
+				tokens.next();
 			}
-			Token::Struct => {
-				tokens.expect_token(Token::Struct)?;
-				let ident = tokens.expect_identifier()?;
+		}
+	}
 
-				tokens.expect_token(Token::LeftBrace)?;
+	(program, errors)
+}
 
-				let mut members = Vec::new();
-				loop {
-					let tk = tokens.expect_typekind()?;
-					let field_ident = tokens.expect_identifier()?;
+fn parse_top_level_item(
+	tokens: &mut impl TokenSource,
+	program: &mut Program,
+	errors: &mut Vec<ParsingError>,
+) -> ParsingResult<()> {
+	let doc = parse_doc_comments(tokens);
+	let token = match tokens.peek() {
+		Some(token) => token.clone(),
+		// a doc comment at the end of the file doesn't document anything
+		None if doc.is_some() => return Ok(()),
+		None => return Err(ParsingError::UnexpectedEndOfInput),
+	};
 
-					members.push((field_ident, tk));
+	match &token.item {
+		Token::In => {
+			tokens.expect_token(Token::In)?;
+			let type_kind = tokens.expect_typekind()?;
+
+			let ident = tokens.expect_identifier()?;
+			program.in_parameters.push(InParameterDeclaration {
+				type_kind,
+				ident,
+				is_uniform: false,
+				doc,
+			});
+		}
+		Token::Uniform => {
+			tokens.expect_token(Token::Uniform)?;
+			let type_kind = tokens.expect_typekind()?;
+
+			let ident = tokens.expect_identifier()?;
+			program.in_parameters.push(InParameterDeclaration {
+				type_kind,
+				ident,
+				is_uniform: true,
+				doc,
+			});
+		}
+		Token::Struct => {
+			tokens.expect_token(Token::Struct)?;
+			let ident = tokens.expect_identifier()?;
 
-					if tokens.maybe_expect(Token::Comma).is_none() {
-						tokens.expect_token(Token::RightBrace)?;
-						break;
-					}
-					if tokens.maybe_expect(Token::RightBrace).is_some() {
-						break;
-					}
-				}
+			tokens.expect_token(Token::LeftBrace)?;
+
+			let mut members = Vec::new();
+			loop {
+				let tk = tokens.expect_typekind()?;
+				let field_ident = tokens.expect_identifier()?;
 
-				program.struct_declarations.push(StructDeclaration {
-					ident,
-					members,
-					size: None,
-				})
+				members.push((field_ident, tk));
+
+				if tokens.maybe_expect(Token::Comma).is_none() {
+					tokens.expect_token(Token::RightBrace)?;
+					break;
+				}
+				if tokens.maybe_expect(Token::RightBrace).is_some() {
+					break;
+				}
 			}
-			// func declarations
-			_ => {
+
+			program.struct_declarations.push(StructDeclaration {
+				ident,
+				members,
+				size: None,
+				doc,
+			})
+		}
+		// func declarations
+		_ => {
+			let tk = tokens.expect_typekind()?;
+			let ident = tokens.expect_identifier()?;
+
+			// arg list
+			tokens.expect_token(Token::LeftParen)?;
+			let mut params = Vec::new();
+			while let Some(tok) = tokens.peek() {
+				if tok.item == Token::RightParen {
+					break;
+				}
 				let tk = tokens.expect_typekind()?;
 				let ident = tokens.expect_identifier()?;
+				params.push((tk, ident));
 
-				// arg list
-				tokens.expect_token(Token::LeftParen)?;
-				let mut params = Vec::new();
-				while let Some(tok) = tokens.peek() {
-					if tok.item == Token::RightParen {
-						break;
-					}
-					let tk = tokens.expect_typekind()?;
-					let ident = tokens.expect_identifier()?;
-					params.push((tk, ident));
-
-					if tokens.maybe_expect(Token::Comma).is_some() {
-						if let Some(next) = tokens.peek() {
-							if next.item == Token::RightParen {
-								Err(ParsingError::UnexpectedToken(next.clone()))?
-							}
+				if tokens.maybe_expect(Token::Comma).is_some() {
+					if let Some(next) = tokens.peek() {
+						if next.item == Token::RightParen {
+							Err(ParsingError::UnexpectedToken(next.clone()))?
 						}
 					}
+				} else if tokens.peek().map(|t| t.item != Token::RightParen).unwrap_or(false) {
+					Err(ParsingError::ExpectedToken(Token::Comma, tokens.peek().unwrap().clone()))?
 				}
-				tokens.expect_token(Token::RightParen)?;
+			}
+			tokens.expect_token(Token::RightParen)?;
 
-				// body
-				tokens.expect_token(Token::LeftBrace)?;
+			// body
+			tokens.expect_token(Token::LeftBrace)?;
 
-				let statements = parse_statements(tokens)?;
+			let statements = parse_statements(tokens, errors);
 
-				program.functions.push(FunctionDeclaration {
-					ident,
-					params,
-					statements,
-					ret_type: tk,
-				});
-			}
+			program.functions.push(FunctionDeclaration {
+				ident,
+				params,
+				statements,
+				ret_type: tk,
+				doc,
+			});
 		}
 	}
 
-	Ok(program)
+	Ok(())
 }
 
-pub fn parse_statements(tokens: &mut impl TokenSource) -> ParsingResult<Vec<Statement>> {
-	let mut output = Vec::new();
+/// Consumes consecutive doc comments, joining them into one line each.
+fn parse_doc_comments(tokens: &mut impl TokenSource) -> Option<String> {
+	let mut lines = Vec::new();
+
+	while let Some(Spanned {
+		item: Token::DocComment(line),
+		..
+	}) = tokens.peek()
+	{
+		lines.push(line.clone());
+		tokens.next();
+	}
 
-	'parsing: loop {
-		let token = tokens.next();
-		if token.is_none() {
-			break 'parsing;
-		}
-		let token = token.unwrap();
+	match lines.is_empty() {
+		true => None,
+		false => Some(lines.join("\n")),
+	}
+}
 
-		match &token.item {
-			Token::Return => {
-				output.push(Statement::Return(token.map(|_| ()), parse_expr_bp(tokens, 0)?));
+/// Doc comments are only attached to top level items, so the ones inside of braces are dropped like normal comments.
+fn strip_nested_doc_comments(tokens: Vec<Spanned<Token>>) -> Vec<Spanned<Token>> {
+	let mut depth = 0;
+
+	tokens
+		.into_iter()
+		.filter(|t| {
+			match t.item {
+				Token::LeftBrace => depth += 1,
+				Token::RightBrace => depth -= 1,
+				Token::DocComment(_) => return depth <= 0,
+				_ => {}
 			}
-			Token::Let => {
-				let is_mut = tokens.maybe_expect(Token::Mut).is_some();
-				let ident = tokens.expect_identifier()?;
-
-				tokens.expect_token(Token::Equals)?;
+			true
+		})
+		.collect()
+}
 
-				output.push(Statement::VariableDeclaration(is_mut, ident, parse_expr_bp(tokens, 0)?));
+/// Skips tokens until the start of the next top level item, or past the end of the (brace delimited) item the parser
+/// is currently stuck in.
+fn synchronize_top_level(tokens: &mut impl TokenSource) {
+	let mut depth = 0;
+
+	while let Some(t) = tokens.peek() {
+		match t.item {
+			Token::In | Token::Uniform | Token::Struct | Token::DocComment(_) if depth == 0 => return,
+			Token::LeftBrace => depth += 1,
+			Token::RightBrace => {
+				tokens.next();
+				depth -= 1;
+				if depth <= 0 {
+					return;
+				}
+				continue;
 			}
-			Token::Identifier(s) => {
-				tokens.expect_token(Token::Equals)?;
+			_ => {}
+		}
 
-				output.push(Statement::Assignment(
-					token.map(|_| s.clone()),
-					parse_expr_bp(tokens, 0)?,
-				));
-			}
-			Token::If => {
-				output.push(Statement::Conditional(parse_conditional(tokens)?));
+		tokens.next();
+	}
+}
+
+/// Skips tokens until something that looks like the beginning of a new statement: a statement keyword, the first
+/// token on a line after the erroneous one, or the closing brace of the current block (which is not consumed).
+fn synchronize_statement(tokens: &mut impl TokenSource, error_line: Option<u32>) {
+	let mut depth = 0;
+
+	while let Some(t) = tokens.peek() {
+		if depth == 0 {
+			match t.item {
+				Token::RightBrace
+				| Token::Let
+				| Token::If
+				| Token::For
+				| Token::While
+				| Token::Break
+				| Token::Continue
+				| Token::Return => return,
+				_ if error_line.map(|l| t.from.line > l).unwrap_or(false) => return,
+				_ => {}
 			}
-			Token::For => {
-				let ident = tokens.expect_identifier()?;
-				tokens.expect_token(Token::Equals)?;
-				let from = parse_expr_bp(tokens, 0)?;
+		}
+
+		match t.item {
+			Token::LeftBrace => depth += 1,
+			Token::RightBrace => depth -= 1,
+			_ => {}
+		}
 
-				tokens.expect_token(Token::To)?;
-				let to = parse_expr_bp(tokens, 0)?;
+		tokens.next();
+	}
+}
 
-				tokens.expect_token(Token::LeftBrace)?;
-				let body = parse_statements(tokens)?;
+/// Parses the statements of a block up to and including the closing brace.
+/// Syntax errors are recorded in `errors` and parsing resumes with the next statement.
+pub fn parse_statements(tokens: &mut impl TokenSource, errors: &mut Vec<ParsingError>) -> Vec<Statement> {
+	let mut output = Vec::new();
 
-				output.push(Statement::Loop(ident, from, to, body));
+	loop {
+		let token = match tokens.next() {
+			Some(token) => token,
+			None => {
+				if !matches!(errors.last(), Some(ParsingError::UnexpectedEndOfInput)) {
+					errors.push(ParsingError::UnexpectedEndOfInput);
+				}
+				break;
 			}
-			Token::RightBrace => break 'parsing,
-			_ => {
-				return Err(ParsingError::UnexpectedToken(token));
+		};
+
+		if token.item == Token::RightBrace {
+			break;
+		}
+
+		match parse_statement(tokens, token, errors) {
+			Ok(statement) => output.push(statement),
+			Err(e) => {
+				let line = e.span().map(|s| s.from.line);
+				errors.push(e);
+				synchronize_statement(tokens, line);
 			}
 		}
 	}
 
-	Ok(output)
+	output
+}
+
+fn parse_statement(
+	tokens: &mut impl TokenSource,
+	token: ItemType,
+	errors: &mut Vec<ParsingError>,
+) -> ParsingResult<Statement> {
+	Ok(match &token.item {
+		Token::Return => {
+			// a `return` without a value is followed by the end of the line or block
+			let value = match tokens.peek() {
+				Some(t) if t.item != Token::RightBrace && t.from.line == token.from.line => {
+					Some(parse_expr_bp(tokens, 0)?)
+				}
+				_ => None,
+			};
+
+			Statement::Return(token.map(|_| ()), value)
+		}
+		Token::Let => {
+			let is_mut = tokens.maybe_expect(Token::Mut).is_some();
+			let ident = tokens.expect_identifier()?;
+
+			tokens.expect_token(Token::Equals)?;
+
+			Statement::VariableDeclaration(is_mut, ident, parse_expr_bp(tokens, 0)?)
+		}
+		Token::Identifier(s) if tokens.peek().map(|t| t.item == Token::LeftParen).unwrap_or(false) => {
+			// a call whose value is discarded
+			tokens.next();
+			Statement::Expression(parse_call(tokens, Reference::unresolved(token.map(|_| s.clone())))?)
+		}
+		Token::Identifier(s) => {
+			let mut place = Expr::Symbol(Reference::unresolved(token.map(|_| s.clone())));
+			while tokens.peek().map(|t| postfix_binding_power(&t.item).is_some()).unwrap_or(false) {
+				let op = tokens.next().unwrap();
+				place = parse_postfix_op(tokens, place, op)?;
+			}
+
+			tokens.expect_token(Token::Equals)?;
+
+			Statement::Assignment(place, parse_expr_bp(tokens, 0)?)
+		}
+		Token::If => Statement::Conditional(parse_conditional(tokens, errors)?),
+		Token::For => {
+			let ident = tokens.expect_identifier()?;
+			tokens.expect_token(Token::Equals)?;
+			let from = parse_expr_bp(tokens, 0)?;
+
+			tokens.expect_token(Token::To)?;
+			let to = parse_expr_bp(tokens, 0)?;
+
+			// `step` is not a keyword, as it is also the name of a builtin function
+			let step = match tokens.peek() {
+				Some(t) if t.item == Token::Identifier("step".to_owned()) => {
+					tokens.next();
+					Some(parse_expr_bp(tokens, 0)?)
+				}
+				_ => None,
+			};
+
+			tokens.expect_token(Token::LeftBrace)?;
+			let body = parse_statements(tokens, errors);
+
+			Statement::Loop(Box::new(ForLoop { ident, from, to, step, body }))
+		}
+		Token::While => {
+			let cond = parse_expr_bp(tokens, 0)?;
+
+			tokens.expect_token(Token::LeftBrace)?;
+			let body = parse_statements(tokens, errors);
+
+			Statement::While(cond, body)
+		}
+		Token::Break => Statement::Break(token.map(|_| ())),
+		Token::Continue => Statement::Continue(token.map(|_| ())),
+		_ => return Err(ParsingError::UnexpectedToken(token)),
+	})
 }
 
-pub fn parse_conditional(tokens: &mut impl TokenSource) -> ParsingResult<Conditional> {
+pub fn parse_conditional(tokens: &mut impl TokenSource, errors: &mut Vec<ParsingError>) -> ParsingResult<Conditional> {
 	// cond
 	let cond = parse_expr_bp(tokens, 0)?;
 
 	// body
 	tokens.expect_token(Token::LeftBrace)?;
-	let body = parse_statements(tokens)?;
+	let body = parse_statements(tokens, errors);
 
 	// recurse
 	let alt = if tokens.maybe_expect(Token::Else).is_some() {
 		if let Some(_) = tokens.maybe_expect(Token::If) {
 			// else if
-			Some(parse_conditional(tokens)?)
+			Some(parse_conditional(tokens, errors)?)
 		} else {
 			// else
 			tokens.expect_token(Token::LeftBrace)?;
-			let last_body = parse_statements(tokens)?;
+			let last_body = parse_statements(tokens, errors);
 			Some(Conditional {
 				cond: None,
 				body: last_body,
@@ -282,50 +591,101 @@ pub fn parse_conditional(tokens: &mut impl TokenSource) -> ParsingResult<Conditi
 }
 
 pub fn infix_binding_power(t: &Token) -> Option<(u8, u8)> {
+	// same precedence as in GLSL, so the generated code doesn't need any extra parentheses
 	match t {
-		Token::Plus | Token::Minus => Some((2, 3)),
-		Token::Star | Token::Slash => Some((4, 5)),
-        Token::Less | Token::LessEq | Token::Greater | Token::GreaterEq | 
-        Token::EqualsEquals | Token::BangEquals => Some((1, 2)),
-        Token::And | Token::Or | Token::Xor => Some((0, 1)),
+		Token::Or => Some((1, 2)),
+		Token::Xor => Some((3, 4)),
+		Token::And => Some((5, 6)),
+		Token::Pipe => Some((7, 8)),
+		Token::Caret => Some((9, 10)),
+		Token::Ampersand => Some((11, 12)),
+		Token::EqualsEquals | Token::BangEquals => Some((13, 14)),
+		Token::Less | Token::LessEq | Token::Greater | Token::GreaterEq => Some((15, 16)),
+		Token::ShiftLeft | Token::ShiftRight => Some((17, 18)),
+		Token::Plus | Token::Minus => Some((19, 20)),
+		Token::Star | Token::Slash | Token::Percent => Some((21, 22)),
 		_ => None,
 	}
 }
 
+pub fn postfix_binding_power(t: &Token) -> Option<(u8, ())> {
+	match t {
+		Token::Dot | Token::LeftBracket => Some((25, ())),
+		_ => None,
+	}
+}
+
+/// Parses the rest of a field access or index expression, after `op` has been consumed.
+fn parse_postfix_op(lexer: &mut impl TokenSource, lhs: Expr, op: ItemType) -> ParsingResult<Expr> {
+	Ok(match op.item {
+		Token::LeftBracket => {
+			let index = parse_expr_bp(lexer, 0)?;
+			let close = lexer.expect_token(Token::RightBracket)?;
+			Expr::Index(Box::new(lhs), Box::new(index), Spanned::encompass((), op, close), None)
+		}
+		_ => Expr::FieldAccess(Box::new(lhs), lexer.expect_identifier()?, None, None),
+	})
+}
+
 pub fn prefix_binding_power(t: &Token) -> Option<((), u8)> {
 	match t {
-        Token::Minus => Some(((), 6)),
-        Token::Bang => Some(((), 7)),
+		Token::Minus | Token::Bang | Token::Tilde => Some(((), 23)),
 		_ => None,
 	}
 }
 
+fn can_begin_expr(t: &Token) -> bool {
+	match t {
+		Token::FloatLiteral(_)
+		| Token::IntegerLiteral(_)
+		| Token::UnsignedIntegerLiteral(_)
+		| Token::True
+		| Token::False
+		| Token::Identifier(_)
+		| Token::LeftParen
+		| Token::LeftBracket => true,
+		t => prefix_binding_power(t).is_some(),
+	}
+}
+
+/// Parses a call to `callee` up to and including the closing parenthesis, after the opening one was consumed.
+fn parse_call(lexer: &mut impl TokenSource, callee: Symbol) -> ParsingResult<Expr> {
+	let mut exprs = Vec::new();
+	while lexer.maybe_expect(Token::RightParen).is_none() {
+		let e = parse_expr_bp(lexer, 0)?;
+		exprs.push(Box::new(e));
+		match lexer.expect_next()? {
+			t if t.item == Token::RightParen => {
+				break;
+			}
+			t if t.item == Token::Comma => {
+				continue;
+			}
+			t => Err(ParsingError::UnexpectedToken(t))?,
+		}
+	}
+
+	Ok(Expr::FuncCall((callee, exprs)))
+}
+
 pub fn parse_expr_bp(lexer: &mut impl TokenSource, min_bp: u8) -> ParsingResult<Expr> {
+	match lexer.peek() {
+		Some(t) if !can_begin_expr(&t.item) => return Err(ParsingError::UnexpectedToken(t.clone())),
+		_ => {}
+	}
+
 	let token = lexer.expect_next()?;
 	// atoms
 	let mut lhs = match &token.item {
 		Token::FloatLiteral(f) => Expr::Literal(token.map(|_| Literal::DecimalLiteral(*f))),
 		Token::IntegerLiteral(i) => Expr::Literal(token.map(|_| Literal::IntegerLiteral(*i))),
+		Token::UnsignedIntegerLiteral(i) => Expr::Literal(token.map(|_| Literal::UnsignedIntegerLiteral(*i))),
+		Token::True => Expr::Literal(token.map(|_| Literal::BoolLiteral(true))),
+		Token::False => Expr::Literal(token.map(|_| Literal::BoolLiteral(false))),
 		Token::Identifier(i) => match lexer.peek() {
 			Some(t) if t.item == Token::LeftParen => {
 				lexer.next();
-
-				let mut exprs = Vec::new();
-				loop {
-					let e = parse_expr_bp(lexer, 0)?;
-					exprs.push(Box::new(e));
-					match lexer.expect_next()? {
-						t if t.item == Token::RightParen => {
-							break;
-						}
-						t if t.item == Token::Comma => {
-							continue;
-						}
-						t => Err(ParsingError::UnexpectedToken(t))?,
-					}
-				}
-
-				Expr::FuncCall((Reference::unresolved(token.map(|_| i.clone())), exprs))
+				parse_call(lexer, Reference::unresolved(token.map(|_| i.clone())))?
 			}
 			Some(t) if t.item == Token::LeftBrace => {
 				let mut lookahead = lexer.clone();
@@ -355,13 +715,6 @@ pub fn parse_expr_bp(lexer: &mut impl TokenSource, min_bp: u8) -> ParsingResult<
 
 				Expr::StructConstruction(token.map(|_| i.clone()), None, fields)
 			}
-			Some(t) if t.item == Token::Dot => {
-				lexer.next();
-
-				let field = lexer.expect_identifier()?;
-
-				Expr::FieldAccess(Reference::unresolved(token.map(|_| i.clone())), field, None, None)
-			}
 			_ => Expr::Symbol(Reference::unresolved(token.map(|_| i.clone()))),
 		},
 		Token::LeftParen => {
@@ -369,6 +722,21 @@ pub fn parse_expr_bp(lexer: &mut impl TokenSource, min_bp: u8) -> ParsingResult<
 			lexer.expect_token(Token::RightParen)?;
 			Expr::Grouped(Box::new(e))
 		}
+		Token::LeftBracket => {
+			let mut elements = Vec::new();
+			let close = loop {
+				elements.push(Box::new(parse_expr_bp(lexer, 0)?));
+
+				if lexer.maybe_expect(Token::Comma).is_none() {
+					break lexer.expect_token(Token::RightBracket)?;
+				}
+				if let Some(close) = lexer.maybe_expect(Token::RightBracket) {
+					break close;
+				}
+			};
+
+			Expr::ArrayLiteral(Spanned::encompass((), token.clone(), close), elements)
+		}
 		t if prefix_binding_power(t).is_some() => {
 			let ((), r_bp) = prefix_binding_power(t).unwrap();
 
@@ -376,6 +744,7 @@ pub fn parse_expr_bp(lexer: &mut impl TokenSource, min_bp: u8) -> ParsingResult<
 			let fnc = match t {
                 Token::Minus => "__op_unary_neg",
                 Token::Bang => "__op_unary_not",
+				Token::Tilde => "__op_unary_bitnot",
 				_ => unreachable!(), // at this point we know we have a valid unary operator, so this is fine
 			};
 
@@ -388,6 +757,16 @@ pub fn parse_expr_bp(lexer: &mut impl TokenSource, min_bp: u8) -> ParsingResult<
 	};
 
 	loop {
+		if let Some((l_bp, ())) = lexer.peek().and_then(|t| postfix_binding_power(&t.item)) {
+			if l_bp < min_bp {
+				break;
+			}
+
+			let op = lexer.next().unwrap();
+			lhs = parse_postfix_op(lexer, lhs, op)?;
+			continue;
+		}
+
 		let (t, (l_bp, r_bp)) = match lexer.peek() {
 			Some(t) if infix_binding_power(t).is_some() => (t.clone(), infix_binding_power(t).unwrap()),
 			_ => break,
@@ -404,6 +783,12 @@ pub fn parse_expr_bp(lexer: &mut impl TokenSource, min_bp: u8) -> ParsingResult<
 			Token::Minus => "__op_binary_sub",
 			Token::Star => "__op_binary_mul",
 			Token::Slash => "__op_binary_div",
+			Token::Percent => "__op_binary_mod",
+			Token::Ampersand => "__op_binary_bitand",
+			Token::Pipe => "__op_binary_bitor",
+			Token::Caret => "__op_binary_bitxor",
+			Token::ShiftLeft => "__op_binary_shl",
+			Token::ShiftRight => "__op_binary_shr",
 			Token::Less => "__op_binary_less",
 			Token::LessEq => "__op_binary_less_equal",
 			Token::Greater => "__op_binary_greater",
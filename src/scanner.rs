@@ -1,4 +1,8 @@
-use crate::ast::{Position, Spanned};
+use crate::{
+	ast::{Position, SourceId, Spanned},
+	diagnostic::Diagnostic,
+};
+use std::fmt;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Token {
@@ -10,7 +14,12 @@ pub enum Token {
 	Else,
 	For,
 	To,
+	While,
+	Break,
+	Continue,
 	Uniform,
+	True,
+	False,
 
 	Struct,
 	Int,
@@ -19,6 +28,8 @@ pub enum Token {
 	RightParen,
 	LeftBrace,
 	RightBrace,
+	LeftBracket,
+	RightBracket,
 	Equals,
 	EqualsEquals,
 	Comma,
@@ -28,6 +39,13 @@ pub enum Token {
 	Minus,
 	Star,
 	Slash,
+	Percent,
+	Ampersand,
+	Pipe,
+	Caret,
+	Tilde,
+	ShiftLeft,
+	ShiftRight,
 	Less,
 	LessEq,
 	Greater,
@@ -45,6 +63,73 @@ pub enum Token {
 	Identifier(String),
 	FloatLiteral(f64),
 	IntegerLiteral(i64),
+	UnsignedIntegerLiteral(u64),
+	/// The text of a `///` comment, without the slashes and the first space.
+	DocComment(String),
+}
+
+impl fmt::Display for Token {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let text = match self {
+			Token::Out => "out",
+			Token::In => "in",
+			Token::Let => "let",
+			Token::Mut => "mut",
+			Token::If => "if",
+			Token::Else => "else",
+			Token::For => "for",
+			Token::To => "to",
+			Token::While => "while",
+			Token::Break => "break",
+			Token::Continue => "continue",
+			Token::Uniform => "uniform",
+			Token::True => "true",
+			Token::False => "false",
+			Token::Struct => "struct",
+			Token::Int => "Int",
+			Token::Float => "Float",
+			Token::LeftParen => "(",
+			Token::RightParen => ")",
+			Token::LeftBrace => "{",
+			Token::RightBrace => "}",
+			Token::LeftBracket => "[",
+			Token::RightBracket => "]",
+			Token::Equals => "=",
+			Token::EqualsEquals => "==",
+			Token::Comma => ",",
+			Token::Colon => ":",
+			Token::Plus => "+",
+			Token::Minus => "-",
+			Token::Star => "*",
+			Token::Slash => "/",
+			Token::Percent => "%",
+			Token::Ampersand => "&",
+			Token::Pipe => "|",
+			Token::Caret => "^",
+			Token::Tilde => "~",
+			Token::ShiftLeft => "<<",
+			Token::ShiftRight => ">>",
+			Token::Less => "<",
+			Token::LessEq => "<=",
+			Token::Greater => ">",
+			Token::GreaterEq => ">=",
+			Token::Bang => "!",
+			Token::BangEquals => "!=",
+			Token::And => "&&",
+			Token::Xor => "^^",
+			Token::Or => "||",
+			Token::Dot => ".",
+			Token::Void => "void",
+			Token::Return => "return",
+			Token::Identifier(i) => return write!(f, "{}", i),
+			Token::FloatLiteral(l) => return write!(f, "{}", l),
+			Token::IntegerLiteral(l) => return write!(f, "{}", l),
+			Token::UnsignedIntegerLiteral(l) => return write!(f, "{}u", l),
+			Token::DocComment(_) => "doc comment",
+		};
+
+		write!(f, "{}", text)
+	}
 }
 
 #[derive(Debug, Clone)]
@@ -58,24 +143,53 @@ pub enum ScanningProduct {
 pub enum ScanningError {
 	UnexpectedCharacter(Spanned<char>),
 	InvalidLiteral(Spanned<()>),
+	UnterminatedBlockComment(Spanned<()>),
 	UnexpectedEndOfFile,
 }
 
+impl From<ScanningError> for Diagnostic {
+	fn from(e: ScanningError) -> Diagnostic {
+		match e {
+			ScanningError::UnexpectedCharacter(c) => Diagnostic::error(format!("unexpected character `{}`", c.item))
+				.with_code("E0101")
+				.with_primary_span(c.just_span()),
+			ScanningError::InvalidLiteral(span) => Diagnostic::error("invalid numeric literal")
+				.with_code("E0102")
+				.with_primary_span(span),
+			ScanningError::UnterminatedBlockComment(span) => Diagnostic::error("unterminated block comment")
+				.with_code("E0104")
+				.with_primary(span, "comment starts here"),
+			ScanningError::UnexpectedEndOfFile => {
+				Diagnostic::error("unexpected end of file while scanning").with_code("E0103")
+			}
+		}
+	}
+}
+
+impl fmt::Display for ScanningError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{}", Diagnostic::from(self.clone()))
+	}
+}
+
 type ScanningResult = Result<ScanningProduct, ScanningError>;
 
 pub struct Scanner<I: Iterator<Item = char>> {
 	input: I,
-	line: u32,
-	offset: u32,
+	position: Position,
 	peeked: Option<char>,
 }
 
 impl<I: Iterator<Item = char>> Scanner<I> {
 	pub fn new(input: I) -> Self {
+		Self::with_source(input, SourceId::default())
+	}
+
+	/// Creates a scanner whose positions are tagged with `source`.
+	pub fn with_source(input: I, source: SourceId) -> Self {
 		Scanner {
 			input,
-			line: 1,
-			offset: 0,
+			position: Position::start(source),
 			peeked: None,
 		}
 	}
@@ -94,15 +208,36 @@ impl<I: Iterator<Item = char>> Scanner<I> {
 		}
 	}
 
+	/// Scans the whole input, skipping over characters that can not be scanned instead of stopping at the first error.
+	pub fn scan_all_recovering(mut self) -> (Vec<Spanned<Token>>, Vec<ScanningError>) {
+		let mut output = Vec::new();
+		let mut errors = Vec::new();
+
+		loop {
+			match self.scan_token() {
+				Ok(ScanningProduct::Skip) => (),
+				Ok(ScanningProduct::Finished) => return (output, errors),
+				Ok(ScanningProduct::Token(token)) => {
+					output.push(token);
+				}
+				Err(e @ ScanningError::UnexpectedEndOfFile) => {
+					errors.push(e);
+					return (output, errors);
+				}
+				Err(e) => errors.push(e),
+			}
+		}
+	}
+
 	pub fn advance(&mut self) -> Option<char> {
-		self.offset += 1;
-		match self.peeked {
+		let c = match self.peeked.take() {
 			None => self.input.next(),
-			Some(c) => {
-				self.peeked = None;
-				Some(c)
-			}
+			c => c,
+		};
+		if let Some(c) = c {
+			self.position = self.position.advance(c);
 		}
+		c
 	}
 
 	pub fn peek(&mut self) -> Option<char> {
@@ -126,17 +261,20 @@ impl<I: Iterator<Item = char>> Scanner<I> {
 			"else" => Some(Token::Else),
 			"for" => Some(Token::For),
 			"to" => Some(Token::To),
+			"while" => Some(Token::While),
+			"break" => Some(Token::Break),
+			"continue" => Some(Token::Continue),
+			"void" => Some(Token::Void),
 			"struct" => Some(Token::Struct),
 			"uniform" => Some(Token::Uniform),
+			"true" => Some(Token::True),
+			"false" => Some(Token::False),
 			_ => None,
 		}
 	}
 
 	pub fn position(&self) -> Position {
-		Position {
-			line: self.line,
-			offset: Some(self.offset),
-		}
+		self.position
 	}
 
 	pub fn scan_token(&mut self) -> ScanningResult {
@@ -149,71 +287,74 @@ impl<I: Iterator<Item = char>> Scanner<I> {
 		};
 		let peeked = self.peek();
 
-		let to = self.position();
-		let tok = |t| Ok(ScanningProduct::Token(Spanned::new(t, from, to)));
-
-		match c {
-			'(' => tok(Token::LeftParen),
-			')' => tok(Token::RightParen),
-			'{' => tok(Token::LeftBrace),
-			'}' => tok(Token::RightBrace),
-			'-' => tok(Token::Minus),
-			'+' => tok(Token::Plus),
+		let token = match c {
+			'(' => Token::LeftParen,
+			')' => Token::RightParen,
+			'{' => Token::LeftBrace,
+			'}' => Token::RightBrace,
+			'[' => Token::LeftBracket,
+			']' => Token::RightBracket,
+			'-' => Token::Minus,
+			'+' => Token::Plus,
 			'/' => match peeked {
-				Some('/') => {
-					while self.advance().ok_or(ScanningError::UnexpectedEndOfFile)? != '\n' {}
-					self.offset = 0;
-					self.line += 1;
-
-					Ok(ScanningProduct::Skip)
-				}
-				_ => tok(Token::Slash),
+				Some('/') => return Ok(self.scan_line_comment(from)),
+				Some('*') => return self.scan_block_comment(from),
+				_ => Token::Slash,
 			},
-			'*' => tok(Token::Star),
-			',' => tok(Token::Comma),
-			'.' => tok(Token::Dot),
+			'*' => Token::Star,
+			'%' => Token::Percent,
+			'~' => Token::Tilde,
+			',' => Token::Comma,
+			'.' if peeked.map(|c| c.is_ascii_digit()).unwrap_or(false) => return self.scan_numerics(from, c),
+			'.' => Token::Dot,
+			'<' if peeked == Some('<') => {
+				self.advance();
+				Token::ShiftLeft
+			}
+			'>' if peeked == Some('>') => {
+				self.advance();
+				Token::ShiftRight
+			}
 			'<' if peeked == Some('=') => {
 				self.advance();
-				tok(Token::LessEq)
+				Token::LessEq
 			}
 			'>' if peeked == Some('=') => {
 				self.advance();
-				tok(Token::GreaterEq)
+				Token::GreaterEq
 			}
 			'=' if peeked == Some('=') => {
 				self.advance();
-				tok(Token::EqualsEquals)
-            }
-            '!' if peeked == Some('=') => {
+				Token::EqualsEquals
+			}
+			'!' if peeked == Some('=') => {
+				self.advance();
+				Token::BangEquals
+			}
+			'&' if peeked == Some('&') => {
 				self.advance();
-				tok(Token::BangEquals)
-            }
-            '&' if peeked == Some('&') => {
-                self.advance();
-                tok(Token::And)
-            }
-            '|' if peeked == Some('|') => {
-                self.advance();
-                tok(Token::Or)
-            }
-            '^' if peeked == Some('^') => {
-                self.advance();
-                tok(Token::Xor)
-            }
-			'<' => tok(Token::Less),
-			'>' => tok(Token::Greater),
-            '=' => tok(Token::Equals),
-            '!' => tok(Token::Bang),
-			':' => tok(Token::Colon),
-
-			'\n' => {
-				self.line += 1;
-				self.offset = 0;
-				Ok(ScanningProduct::Skip)
+				Token::And
 			}
-			c if c.is_whitespace() => Ok(ScanningProduct::Skip),
-			c if c.is_numeric() => self.scan_numerics(c),
-			c if c.is_alphanumeric() || c == '_' => self.scan_identifier(c),
+			'|' if peeked == Some('|') => {
+				self.advance();
+				Token::Or
+			}
+			'^' if peeked == Some('^') => {
+				self.advance();
+				Token::Xor
+			}
+			'&' => Token::Ampersand,
+			'|' => Token::Pipe,
+			'^' => Token::Caret,
+			'<' => Token::Less,
+			'>' => Token::Greater,
+			'=' => Token::Equals,
+			'!' => Token::Bang,
+			':' => Token::Colon,
+
+			c if c.is_whitespace() => return Ok(ScanningProduct::Skip),
+			c if c.is_ascii_digit() => return self.scan_numerics(from, c),
+			c if c.is_alphanumeric() || c == '_' => return self.scan_identifier(from, c),
 			c => {
 				return Err(ScanningError::UnexpectedCharacter(Spanned::new(
 					c,
@@ -221,13 +362,67 @@ impl<I: Iterator<Item = char>> Scanner<I> {
 					self.position(),
 				)))
 			}
+		};
+
+		Ok(ScanningProduct::Token(Spanned::new(token, from, self.position())))
+	}
+
+	/// Scans the rest of a comment starting with `//`. Comments starting with exactly three slashes are doc comments.
+	fn scan_line_comment(&mut self, from: Position) -> ScanningProduct {
+		self.advance();
+
+		let mut is_doc = false;
+		if self.peek() == Some('/') {
+			self.advance();
+			is_doc = self.peek() != Some('/');
+		}
+
+		let mut text = String::new();
+		while let Some(c) = self.peek() {
+			if c == '\n' {
+				break;
+			}
+			text.push(self.advance().unwrap());
+		}
+
+		if is_doc {
+			let text = text.strip_prefix(' ').unwrap_or(&text).trim_end().to_owned();
+			ScanningProduct::Token(Spanned::new(Token::DocComment(text), from, self.position()))
+		} else {
+			ScanningProduct::Skip
 		}
 	}
 
-	pub fn scan_identifier(&mut self, begin: char) -> ScanningResult {
-		let mut from = self.position();
-		from.offset = from.offset.map(|v| v - 1);
+	/// Scans the rest of a `/* ... */` comment. Block comments nest.
+	fn scan_block_comment(&mut self, from: Position) -> ScanningResult {
+		self.advance();
 
+		let mut depth = 1;
+		while depth > 0 {
+			match self.advance() {
+				Some('/') if self.peek() == Some('*') => {
+					self.advance();
+					depth += 1;
+				}
+				Some('*') if self.peek() == Some('/') => {
+					self.advance();
+					depth -= 1;
+				}
+				Some(_) => {}
+				None => {
+					return Err(ScanningError::UnterminatedBlockComment(Spanned::new(
+						(),
+						from,
+						self.position(),
+					)))
+				}
+			}
+		}
+
+		Ok(ScanningProduct::Skip)
+	}
+
+	pub fn scan_identifier(&mut self, from: Position, begin: char) -> ScanningResult {
 		let mut ident = String::new();
 		ident.push(begin);
 
@@ -248,34 +443,113 @@ impl<I: Iterator<Item = char>> Scanner<I> {
 		})
 	}
 
-	pub fn scan_numerics(&mut self, begin: char) -> ScanningResult {
-		let mut from = self.position();
-		from.offset = from.offset.map(|v| v - 1);
+	/// Scans a numeric literal starting with `begin`, which is either a digit or the `.` of a float like `.5`.
+	///
+	/// Integers may be written in hex (`0xff`) or binary (`0b101`), floats may have an exponent (`1e-3`), digits can be
+	/// separated with `_`, and the suffixes `u` and `f` make a literal unsigned or floating point respectively.
+	pub fn scan_numerics(&mut self, from: Position, begin: char) -> ScanningResult {
+		let radix = match (begin, self.peek()) {
+			('0', Some('x')) | ('0', Some('X')) => 16,
+			('0', Some('b')) | ('0', Some('B')) => 2,
+			_ => 10,
+		};
 
 		let mut text = String::new();
-		text.push(begin);
+		let mut is_float = begin == '.';
+		let mut is_unsigned = false;
 
-		while self.peek().unwrap().is_numeric() {
-			text.push(self.advance().unwrap());
-		}
+		if radix == 10 {
+			text.push(begin);
+			self.scan_digits(&mut text, 10);
 
-		if self.peek().unwrap() == '.' {
-			text.push(self.advance().unwrap());
-			while self.peek().unwrap().is_numeric() {
+			if !is_float && self.peek() == Some('.') {
+				text.push(self.advance().unwrap());
+				self.scan_digits(&mut text, 10);
+				is_float = true;
+			}
+
+			if let Some('e') | Some('E') = self.peek() {
 				text.push(self.advance().unwrap());
+				if let Some(sign @ '+') | Some(sign @ '-') = self.peek() {
+					self.advance();
+					text.push(sign);
+				}
+				if !self.scan_digits(&mut text, 10) {
+					return self.invalid_literal(from);
+				}
+				is_float = true;
+			}
+		} else {
+			// skip the radix prefix
+			self.advance();
+			if !self.scan_digits(&mut text, radix) {
+				return self.invalid_literal(from);
 			}
-			let to = self.position();
+		}
 
-			match text.parse::<f64>() {
-				Ok(f) => Ok(ScanningProduct::Token(Spanned::new(Token::FloatLiteral(f), from, to))),
-				Err(_) => Err(ScanningError::InvalidLiteral(Spanned::new((), from, to))),
+		match self.peek() {
+			Some('f') | Some('F') if radix == 10 => {
+				self.advance();
+				is_float = true;
+			}
+			Some('u') | Some('U') if !is_float => {
+				self.advance();
+				is_unsigned = true;
 			}
+			_ => {}
+		}
+
+		// something like `1.0x` or `0b102`
+		if self.peek().map(|c| c.is_alphanumeric() || c == '_').unwrap_or(false) {
+			return self.invalid_literal(from);
+		}
+
+		let to = self.position();
+		let token = if is_float {
+			text.parse::<f64>().ok().map(Token::FloatLiteral)
 		} else {
-			let to = self.position();
-			match text.parse::<i64>() {
-				Ok(i) => Ok(ScanningProduct::Token(Spanned::new(Token::IntegerLiteral(i), from, to))),
-				Err(_) => Err(ScanningError::InvalidLiteral(Spanned::new((), from, to))),
+			// out of range literals are rejected, `Int` literals wrap around like they do in GLSL
+			match u64::from_str_radix(&text, radix) {
+				Ok(i) if i <= u32::MAX as u64 && is_unsigned => Some(Token::UnsignedIntegerLiteral(i)),
+				Ok(i) if i <= u32::MAX as u64 => Some(Token::IntegerLiteral(i as u32 as i32 as i64)),
+				_ => None,
 			}
+		};
+
+		match token {
+			Some(token) => Ok(ScanningProduct::Token(Spanned::new(token, from, to))),
+			None => Err(ScanningError::InvalidLiteral(Spanned::new((), from, to))),
 		}
 	}
+
+	/// Appends digits in the given radix to `text`, skipping `_` separators. Returns whether any digit was found.
+	fn scan_digits(&mut self, text: &mut String, radix: u32) -> bool {
+		let mut any = false;
+
+		while let Some(c) = self.peek() {
+			if c.is_digit(radix) {
+				text.push(self.advance().unwrap());
+				any = true;
+			} else if c == '_' {
+				self.advance();
+			} else {
+				break;
+			}
+		}
+
+		any
+	}
+
+	/// Skips the rest of a malformed literal and reports it.
+	fn invalid_literal(&mut self, from: Position) -> ScanningResult {
+		while let Some(c) = self.peek() {
+			if c.is_alphanumeric() || c == '_' || c == '.' {
+				self.advance();
+			} else {
+				break;
+			}
+		}
+
+		Err(ScanningError::InvalidLiteral(Spanned::new((), from, self.position())))
+	}
 }
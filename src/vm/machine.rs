@@ -1,6 +1,9 @@
 use super::*;
-use crate::compiler::program_data::ProgramData;
-use std::mem;
+use crate::{
+	builtins::{Sampler2D, Texture},
+	compiler::program_data::{signature, ProgramData},
+};
+use std::{mem, sync::Arc};
 
 #[derive(Debug, Clone)]
 pub struct VMProgram {
@@ -31,6 +34,10 @@ pub struct VirtualMachine<'a> {
 	pub isp: usize,
 	pub stack_base: usize,
 	pub breakpoints: Vec<u16>,
+	/// The signature of the function started with `run_fn`, its return value holds the outputs of the program.
+	pub entry_point: Option<String>,
+	/// The textures bound to `Sampler2D` uniforms, the handle `n` refers to the texture at `n - 1`.
+	pub textures: Vec<Arc<Texture>>,
 }
 
 const INITIAL_STACK_CAPACITY: usize = 128; // should be large enough?
@@ -47,6 +54,8 @@ impl<'a> VirtualMachine<'a> {
 			call_stack: Vec::with_capacity(8), // should always be enough
 			stack_base: program.data.static_section_size,
 			breakpoints: vec![],
+			entry_point: None,
+			textures: vec![],
 		}
 	}
 
@@ -93,6 +102,26 @@ impl<'a> VirtualMachine<'a> {
 		unsafe { self.load_stack(offset) }
 	}
 
+	/// Binds a texture to the `Sampler2D` uniform `ident`, replacing the texture bound to it before.
+	pub fn bind_texture(&mut self, ident: &str, texture: impl Into<Arc<Texture>>) {
+		let texture = texture.into();
+
+		match self.get_global::<Sampler2D>(ident) {
+			Sampler2D(0) => {
+				self.textures.push(texture);
+				let handle = Sampler2D(self.textures.len() as u32);
+				self.set_global(ident, handle);
+			}
+			Sampler2D(handle) => self.textures[handle as usize - 1] = texture,
+		}
+	}
+
+	/// The texture a sampler refers to, `None` if no texture is bound to it.
+	pub fn texture(&self, sampler: Sampler2D) -> Option<&Texture> {
+		let index = (sampler.0 as usize).checked_sub(1)?;
+		self.textures.get(index).map(|t| &**t)
+	}
+
 	pub fn get_out_float(&mut self, ident: &str) -> f32 {
 		let offset = self
 			.program
@@ -107,8 +136,14 @@ impl<'a> VirtualMachine<'a> {
 	}
 
 	pub fn run_fn(mut self, id: &str, breakpoints: Vec<u16>) -> VMState<'a> {
-		let fnc = self.program.data.functions.get(id).unwrap();
-		self.isp = fnc.address.unwrap();
+		// entry points can be given by name, as they take no parameters
+		let functions = &self.program.data.functions;
+		let key = match functions.contains_key(id) {
+			true => id.to_owned(),
+			false => signature(id, &[]),
+		};
+		self.isp = functions.get(&key).unwrap().address.unwrap();
+		self.entry_point = Some(key);
 		self.stack_base = self.stack.len();
 		self.breakpoints = breakpoints;
 
@@ -173,6 +208,53 @@ impl<'a> VirtualMachine<'a> {
 					let val = self.load_stack::<u32>(p as usize);
 					self.push_stack_raw(std::mem::transmute(val));
 				},
+				OpCode::Peek4 => unsafe {
+					let val = self.load_stack::<u32>(self.stack.len() - 4 - p as usize);
+					self.push_stack_raw(val);
+				},
+				OpCode::Discard => {
+					let kept = self.program.code[self.isp].data as usize;
+					let end = self.stack.len() - kept;
+					self.stack.drain((end - p as usize)..end);
+					self.isp += 1;
+				}
+				OpCode::LocalAddr => self.push_stack_raw((self.stack_base + p as usize) as u32),
+				OpCode::Index => {
+					let element_size = self.program.code[self.isp].data;
+					self.isp += 1;
+
+					let index: u32 = unsafe { self.pop_stack() };
+					let address: u32 = unsafe { self.pop_stack() };
+					if index >= p as u32 {
+						return self.error(VMError::IndexOutOfBounds { index, len: p as u32 });
+					}
+
+					self.push_stack_raw(address + index * element_size);
+				}
+				OpCode::Load4Addr => unsafe {
+					let address: u32 = self.pop_stack();
+					let val = self.load_stack::<u32>(address as usize + p as usize);
+					self.push_stack_raw(val);
+				},
+				OpCode::Mov4Addr => unsafe {
+					let address: u32 = self.pop_stack();
+					let val: u32 = self.pop_stack();
+					self.write_stack(address as usize + p as usize, val);
+					self.push_stack_raw(address);
+				},
+				OpCode::Extract => {
+					let element_size = self.program.code[self.isp].data as usize;
+					self.isp += 1;
+
+					let index: u32 = unsafe { self.pop_stack() };
+					if index >= p as u32 {
+						return self.error(VMError::IndexOutOfBounds { index, len: p as u32 });
+					}
+
+					let array = self.pop_bytes(p as usize * element_size);
+					let element = index as usize * element_size;
+					self.push_bytes(&array[element..element + element_size]);
+				}
 				OpCode::Void => self.push_stack_raw(0),
 				OpCode::Ret => {
 					// we need to figure out the amount of bytes to buffer
@@ -203,6 +285,10 @@ impl<'a> VirtualMachine<'a> {
 		}
 	}
 
+	fn error(self, error: VMError) -> VMState<'a> {
+		VMState::Error(VMErrorState(self, error))
+	}
+
 	pub fn push_bytes(&mut self, bytes: &[u8]) {
 		self.stack.extend_from_slice(bytes);
 	}
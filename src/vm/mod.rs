@@ -18,6 +18,22 @@ pub enum OpCode {
 	Load4,
 	Mov4Global,
 	Load4Global,
+	/// Pushes a copy of the word that ends the given number of bytes below the top of the stack.
+	Peek4,
+	/// Removes the given number of bytes from below the top `n` bytes of the stack, where `n` is the next cell.
+	Discard,
+	/// Pushes the absolute address of the given offset in the current stack frame.
+	LocalAddr,
+	/// Pops an index and an address, and pushes the address of the indexed element of the array there. The data is
+	/// the length of the array, the next cell the size of an element.
+	Index,
+	/// Pops an address and pushes the word at the given offset from it.
+	Load4Addr,
+	/// Pops the word below the address on top of the stack, and writes it to the given offset from the address.
+	Mov4Addr,
+	/// Pops an index and an array, and pushes the indexed element. The data is the length of the array, the next cell
+	/// the size of an element.
+	Extract,
 	JmpNotZero,
 	JmpZero,
 	Jmp,
@@ -33,16 +49,38 @@ pub enum OpCode {
 pub struct VMBreakpointState<'a>(VirtualMachine<'a>, u16);
 pub struct VMRunFinishedState<'a>(pub VirtualMachine<'a>);
 
+pub struct VMErrorState<'a>(pub VirtualMachine<'a>, pub VMError);
+
 pub enum VMState<'a> {
 	BreakpointEncountered(VMBreakpointState<'a>),
 	VMRunFinished(VMRunFinishedState<'a>),
+	Error(VMErrorState<'a>),
+}
+
+/// An error that aborts the execution of a program.
+#[derive(Clone, Debug, PartialEq)]
+pub enum VMError {
+	IndexOutOfBounds { index: u32, len: u32 },
+}
+
+impl std::fmt::Display for VMError {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			VMError::IndexOutOfBounds { index, len } => {
+				write!(f, "index {} is out of bounds for an array of length {}", *index as i32, len)
+			}
+		}
+	}
 }
 
+impl std::error::Error for VMError {}
+
 impl<'a> VMState<'a> {
 	pub fn unwrap_vm(self) -> VirtualMachine<'a> {
 		match self {
 			VMState::BreakpointEncountered(VMBreakpointState(vm, _)) => vm,
 			VMState::VMRunFinished(VMRunFinishedState(vm)) => vm,
+			VMState::Error(VMErrorState(vm, _)) => vm,
 		}
 	}
 }
@@ -101,6 +139,52 @@ impl<'a> VMRunFinishedState<'a> {
 		// TODO
 		self.0
 	}
+
+	/// The outputs of the program, i.e. the value returned by the entry point. A returned struct has one output per
+	/// field, named like the field. Any other value is a single output named `out_0`, like in the generated GLSL.
+	pub fn outputs(&self) -> Vec<(String, (TypeKind, Vec<u8>))> {
+		let vm = &self.0;
+		let return_type = vm
+			.entry_point
+			.as_ref()
+			.and_then(|key| vm.program.data.functions.get(key))
+			.and_then(|f| f.return_type.clone())
+			.unwrap_or(TypeKind::Void);
+
+		// the return value is left on top of the stack
+		let mut offset = vm.stack.len() - return_type.size();
+
+		match &return_type {
+			TypeKind::Void => vec![],
+			TypeKind::Struct(s) => s
+				.borrow()
+				.members
+				.iter()
+				.map(|(f, tk)| {
+					let bytes = vm.read_stack_bytes(offset, tk.size()).to_vec();
+					offset += tk.size();
+
+					(f.item.clone(), (tk.item.clone(), bytes))
+				})
+				.collect(),
+			tk => {
+				let bytes = vm.read_stack_bytes(offset, tk.size()).to_vec();
+				vec![("out_0".to_owned(), (tk.clone(), bytes))]
+			}
+		}
+	}
+
+	/// The output with the given name, see `outputs`. `None` if there is no such output, or `T` doesn't have its size.
+	pub fn get_output<T: bytemuck::Pod>(&self, name: &str) -> Option<T> {
+		let (_, (_, bytes)) = self.outputs().into_iter().find(|(n, _)| n == name)?;
+		if bytes.len() != std::mem::size_of::<T>() {
+			return None;
+		}
+
+		let mut value: T = bytemuck::Zeroable::zeroed();
+		bytemuck::bytes_of_mut(&mut value).copy_from_slice(&bytes);
+		Some(value)
+	}
 }
 
 #[derive(Clone)]
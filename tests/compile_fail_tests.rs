@@ -1,18 +1,28 @@
-use motokigo::{compiler, glsl, parser};
+use motokigo::{ast::TypeKind, compiler, compiler::resolve_types::TypeError, parser};
 
 macro_rules! should_fail_compilation {
 	($sn: ident, $source: expr) => {
 		paste::item! {
 			#[test]
-			#[should_panic]
 			pub fn [<fail_compile_ $sn>]() {
-				let mut program = parser::parse($source).unwrap();
-				compiler::resolve_types::resolve(&mut program, &mut compiler::program_data::ProgramData::new()).unwrap();
-
-				// check that glsl compiler atleast works
-				glsl::generate_glsl(program.clone());
+				let program = parser::parse($source).unwrap();
+				assert!(compiler::compile(program).is_err());
+			}
+		}
+	};
+}
 
-				compiler::compile(program);
+/// Like `should_fail_compilation`, but checks that type checking reports exactly the one expected error.
+macro_rules! should_fail_type_check {
+	($sn: ident, $error: pat, $source: expr) => {
+		paste::item! {
+			#[test]
+			pub fn [<fail_type_check_ $sn>]() {
+				let mut program = parser::parse($source).unwrap();
+				let errors =
+					compiler::resolve_types::resolve(&mut program, &mut compiler::program_data::ProgramData::new())
+						.unwrap_err();
+				assert!(matches!(errors[..], [$error]), "{:?}", errors);
 			}
 		}
 	};
@@ -34,7 +44,7 @@ Float foo(Float a) {
 }
 
 Float main() {
-	return foo(5)
+	return foo(true)
 }
 "
 );
@@ -59,3 +69,605 @@ Float main() {
 }
 "
 );
+
+should_fail_compilation!(
+	struct_construction_missing_field,
+	r"
+struct Foo {
+	Float x,
+	Float y
+}
+
+Float main() {
+	let a = Foo { x: 1.0 }
+	return a.x
+}
+"
+);
+
+should_fail_compilation!(
+	struct_construction_wrong_field_type,
+	r"
+struct Foo {
+	Float x
+}
+
+Float main() {
+	let a = Foo { x: 1 }
+	return a.x
+}
+"
+);
+
+should_fail_compilation!(
+	local_shadows_global,
+	r"
+in Float x
+
+Float main() {
+	let x = 1.0
+	return x
+}
+"
+);
+
+should_fail_compilation!(
+	int_condition,
+	r"
+Float main() {
+	if 1 {
+		return 1.0
+	}
+	return 0.0
+}
+"
+);
+
+should_fail_compilation!(
+	int_logic_op,
+	r"
+Float main() {
+	let a = 1 && 0
+	return 0.0
+}
+"
+);
+
+#[test]
+pub fn compile_returns_errors() {
+	let program = parser::parse(
+		r"
+in Float x
+
+Float main() {
+	let x = 1.0
+	return x
+}",
+	)
+	.unwrap();
+
+	let error = compiler::compile(program).unwrap_err();
+	assert!(matches!(error, compiler::CompileError::RedeclaredGlobal(_)));
+}
+
+#[test]
+pub fn reports_all_type_errors() {
+	let mut program = parser::parse(
+		r"
+Float main() {
+	let a = foo(1.0)
+	let b = a + 1.0
+	let c = unknown
+	return 1
+}",
+	)
+	.unwrap();
+
+	let errors = compiler::resolve_types::resolve(&mut program, &mut compiler::program_data::ProgramData::new())
+		.unwrap_err();
+
+	// `a + 1.0` is not reported, since `a` is already poisoned by the unknown function
+	assert_eq!(errors.len(), 3);
+	assert!(matches!(errors[0], TypeError::UnknownFunction(_, _)));
+	assert!(matches!(errors[1], TypeError::UnknownSymbol(_)));
+	assert!(matches!(errors[2], TypeError::TypeMismatch(_, TypeKind::F32, TypeKind::I32)));
+}
+
+should_fail_compilation!(
+	swizzle_write_repeats_component,
+	r"
+Vec2 main() {
+	let mut v = Vec2(1.0, 2.0)
+	v.xx = Vec2(3.0, 4.0)
+	return v
+}"
+);
+
+should_fail_compilation!(
+	swizzle_out_of_range,
+	r"
+Float main() {
+	let v = Vec2(1.0, 2.0)
+	return v.z
+}"
+);
+
+should_fail_compilation!(
+	field_assignment_to_immutable,
+	r"
+Vec3 main() {
+	let v = Vec3(1.0, 2.0, 3.0)
+	v.y = 5.0
+	return v
+}"
+);
+
+should_fail_compilation!(
+	constant_index_out_of_bounds,
+	r"
+Float main() {
+	let a = [1.0, 2.0]
+	return a[2]
+}"
+);
+
+should_fail_compilation!(
+	index_non_array,
+	r"
+Float main() {
+	let a = 1.0
+	return a[0]
+}"
+);
+
+should_fail_compilation!(
+	float_index,
+	r"
+Float main() {
+	let a = [1.0, 2.0]
+	return a[1.0]
+}"
+);
+
+should_fail_compilation!(
+	mixed_array_literal,
+	r"
+Float[2] main() {
+	return [1.0, 2]
+}"
+);
+
+should_fail_compilation!(
+	call_ambiguous_with_builtin,
+	r"
+Float abs(Float x) {
+	return x
+}
+
+Float main() {
+	return abs(1.0)
+}"
+);
+
+should_fail_compilation!(
+	no_matching_overload,
+	r"
+Float scale(Float x) {
+	return x
+}
+
+Float scale(Vec2 x) {
+	return x.x
+}
+
+Float main() {
+	return scale(true)
+}"
+);
+
+should_fail_type_check!(
+	too_many_arguments,
+	TypeError::WrongArgumentCount(_, _, 2),
+	r"
+Float foo(Float a) {
+	return a
+}
+
+Float main() {
+	return foo(1.0, 2.0)
+}"
+);
+
+should_fail_type_check!(
+	builtin_argument_count,
+	TypeError::WrongArgumentCount(_, _, 1),
+	r"
+Float main() {
+	return dot(Vec3(1.0, 0.0, 0.0))
+}"
+);
+
+should_fail_type_check!(
+	duplicate_function,
+	TypeError::Redefinition(_, _, "function"),
+	r"
+Float foo(Float a) {
+	return a
+}
+
+Float foo(Float b) {
+	return b * 2.0
+}
+
+Float main() {
+	return foo(1.0)
+}"
+);
+
+should_fail_type_check!(
+	duplicate_struct,
+	TypeError::Redefinition(_, _, "struct"),
+	r"
+struct Foo {
+	Float x
+}
+
+struct Foo {
+	Float y
+}
+
+Float main() {
+	return 1.0
+}"
+);
+
+should_fail_type_check!(
+	duplicate_field,
+	TypeError::Redefinition(_, _, "field"),
+	r"
+struct Foo {
+	Float x,
+	Vec2 x
+}
+
+Float main() {
+	return 1.0
+}"
+);
+
+should_fail_type_check!(
+	duplicate_global,
+	TypeError::Redefinition(_, _, "global"),
+	r"
+in Float x
+uniform Vec3 x
+
+Float main() {
+	return 1.0
+}"
+);
+
+should_fail_type_check!(
+	duplicate_parameter,
+	TypeError::Redefinition(_, _, "parameter"),
+	r"
+Float foo(Float a, Float a) {
+	return a
+}
+
+Float main() {
+	return foo(1.0, 2.0)
+}"
+);
+
+// there is no shadowing inside of a function: a variable can't reuse the name of a parameter or another variable
+should_fail_type_check!(
+	local_redeclares_parameter,
+	TypeError::Redefinition(_, _, "variable"),
+	r"
+Float foo(Float a) {
+	let a = 2.0
+	return a
+}
+
+Float main() {
+	return foo(1.0)
+}"
+);
+
+should_fail_type_check!(
+	local_redeclares_local,
+	TypeError::Redefinition(_, _, "variable"),
+	r"
+Float main() {
+	let a = 1.0
+	let a = 2.0
+	return a
+}"
+);
+
+should_fail_type_check!(
+	loop_variable_redeclares_local,
+	TypeError::Redefinition(_, _, "variable"),
+	r"
+Float main() {
+	let i = 1
+	for i=0 to 10 {
+	}
+	return 1.0
+}"
+);
+
+should_fail_type_check!(
+	nested_loops_reuse_variable,
+	TypeError::Redefinition(_, _, "variable"),
+	r"
+Float main() {
+	for i=0 to 10 {
+		for i=0 to 10 {
+		}
+	}
+	return 1.0
+}"
+);
+
+should_fail_type_check!(
+	variable_used_after_block,
+	TypeError::OutOfScope(_, _),
+	r"
+Float main() {
+	if true {
+		let a = 1.0
+	}
+	return a
+}"
+);
+
+should_fail_type_check!(
+	loop_variable_used_after_loop,
+	TypeError::OutOfScope(_, _),
+	r"
+Int main() {
+	for i=0 to 10 {
+	}
+	return i
+}"
+);
+
+should_fail_type_check!(
+	direct_recursion,
+	TypeError::Recursion(_, _),
+	r"
+Int factorial(Int n) {
+	if n < 2 {
+		return 1
+	}
+	return n * factorial(n - 1)
+}
+
+Int main() {
+	return factorial(3)
+}"
+);
+
+should_fail_type_check!(
+	mutual_recursion,
+	TypeError::Recursion(_, _),
+	r"
+Bool is_even(Int n) {
+	if n == 0 {
+		return true
+	}
+	return is_odd(n - 1)
+}
+
+Bool is_odd(Int n) {
+	if n == 0 {
+		return false
+	}
+	return is_even(n - 1)
+}
+
+Bool main() {
+	return is_even(4)
+}"
+);
+
+should_fail_type_check!(
+	ambiguous_conversion,
+	TypeError::AmbiguousCall(_, _),
+	r"
+Float f(Float a, Int b) {
+	return a
+}
+
+Float f(Int a, Float b) {
+	return b
+}
+
+Float main() {
+	return f(1, 2)
+}"
+);
+
+should_fail_type_check!(
+	recursive_struct,
+	TypeError::RecursiveStruct(_, _),
+	r"
+struct Node {
+	Float value,
+	Node[2] children
+}
+
+Float main() {
+	return 1.0
+}"
+);
+
+should_fail_type_check!(
+	sampler_in_variable,
+	TypeError::OpaqueType(_, TypeKind::Sampler2D, _),
+	r"
+uniform Sampler2D tex
+
+Vec4 main() {
+	let t = tex
+	return texture(t, Vec2(0.5, 0.5))
+}"
+);
+
+should_fail_type_check!(
+	matrix_output,
+	TypeError::InvalidOutput(_, TypeKind::Matrix(_, 2, 2), _),
+	r"
+struct Output {
+	Vec4 color,
+	Mat2 transform
+}
+
+Output main() {
+	return Output { color: Vec4(1.0), transform: Mat2(1.0) }
+}"
+);
+
+should_fail_type_check!(
+	struct_output,
+	TypeError::InvalidOutput(_, TypeKind::Struct(_), _),
+	r"
+struct Inner {
+	Bool flag
+}
+
+struct Output {
+	Inner inner
+}
+
+Output main() {
+	return Output { inner: Inner { flag: true } }
+}"
+);
+
+should_fail_type_check!(
+	sampler_in_struct,
+	TypeError::OpaqueType(_, TypeKind::Sampler2D, _),
+	r"
+struct Material {
+	Sampler2D albedo
+}
+
+Float main() {
+	return 1.0
+}"
+);
+
+should_fail_type_check!(
+	non_uniform_sampler,
+	TypeError::OpaqueType(_, TypeKind::Sampler2D, _),
+	r"
+in Sampler2D tex
+
+Vec4 main() {
+	return texture(tex, Vec2(0.5, 0.5))
+}"
+);
+
+should_fail_type_check!(
+	break_outside_loop,
+	TypeError::JumpOutsideLoop(_, "break"),
+	r"
+Float main() {
+	if true {
+		break
+	}
+	return 1.0
+}"
+);
+
+should_fail_type_check!(
+	non_constant_loop_step,
+	TypeError::InvalidLoopStep(_),
+	r"
+uniform Int n
+
+Int main() {
+	let mut sum = 0
+	for i = 0 to 10 step n {
+		sum = sum + i
+	}
+	return sum
+}"
+);
+
+should_fail_type_check!(
+	zero_loop_step,
+	TypeError::InvalidLoopStep(_),
+	r"
+Int main() {
+	let mut sum = 0
+	for i = 0 to 10 step 0 {
+		sum = sum + i
+	}
+	return sum
+}"
+);
+
+should_fail_type_check!(
+	mismatched_loop_bound,
+	TypeError::TypeMismatch(_, TypeKind::F32, TypeKind::I32),
+	r"
+Float main() {
+	let mut sum = 0.0
+	for t = 0.0 to 10 {
+		sum = sum + t
+	}
+	return sum
+}"
+);
+
+should_fail_type_check!(
+	missing_return_without_else,
+	TypeError::MissingReturn(_, _),
+	r"
+uniform Float x
+
+Float main() {
+	if x > 0.0 {
+		return 1.0
+	} else if x < 0.0 {
+		return -1.0
+	}
+}"
+);
+
+should_fail_type_check!(
+	missing_return_after_loop,
+	TypeError::MissingReturn(_, _),
+	r"
+Int main() {
+	for i = 0 to 10 {
+		return i
+	}
+}"
+);
+
+should_fail_type_check!(
+	bare_return_from_value_function,
+	TypeError::TypeMismatch(_, TypeKind::F32, TypeKind::Void),
+	r"
+Float main() {
+	return
+}"
+);
+
+should_fail_type_check!(
+	void_in_variable,
+	TypeError::VoidValue(_, _),
+	r"
+Void nothing() {
+}
+
+Float main() {
+	let x = nothing()
+	return 1.0
+}"
+);
@@ -1,4 +1,4 @@
-use motokigo::{compiler, glsl, parser};
+use motokigo::{ast::*, compiler, glsl, parser};
 
 macro_rules! should_pass_compilation {
 	($sn: ident, $source: expr) => {
@@ -11,7 +11,7 @@ macro_rules! should_pass_compilation {
 				// check that glsl compiler atleast works
 				glsl::generate_glsl(program.clone());
 
-				compiler::compile(program);
+				compiler::compile(program).unwrap();
 			}
 		}
 	};
@@ -21,9 +21,9 @@ should_pass_compilation!(
 	if_statements,
 	r"
 Float main(){
-	if 1 {
+	if true {
 		return 2.0
-	} else if 0 {
+	} else if false {
 		return 5.0
 	} else {
 		return 1.0
@@ -72,6 +72,49 @@ Float main() {
 "
 );
 
+should_pass_compilation!(
+	global_field_access,
+	r"
+in Vec3 normal
+
+Float main() {
+	return normal.y + normal.z
+}
+"
+);
+
+should_pass_compilation!(
+	numeric_literals,
+	r"
+UInt flags() {
+	return 0b1010u
+}
+
+Float main() {
+	let scale = 1e-3 + .5 + 2f
+	let mask = 0xff_ff
+	return scale
+}
+"
+);
+
+should_pass_compilation!(
+	integer_vectors,
+	r"
+in IVec2 pixel
+uniform UVec4 seed
+
+BVec2 inside(IVec2 p, IVec2 size) {
+	return lessThan(p, size)
+}
+
+Vec2 main() {
+	let hashed = seed.xy + UVec2(pixel)
+	let mask = inside(pixel, IVec2(640, 480))
+	return Vec2(pixel) * 0.5
+}
+"
+);
 
 should_pass_compilation!(mandelbrot, r"
 in Float ux
@@ -109,4 +152,290 @@ Vec3 main() {
 
     return Vec3(float(steps) / 15.0, 0.0, 0.0)
 }
-");
\ No newline at end of file
+");
+should_pass_compilation!(
+	field_access_chains,
+	r"
+struct Material {
+	Vec4 color,
+	Float roughness
+}
+
+Material base() {
+	return Material { color: Vec4(1.0, 0.5, 0.25, 1.0), roughness: 0.5 }
+}
+
+Vec3 main() {
+	let mut m = base()
+	m.color.rgb = normalize(m.color.xyz).zyx
+	m.roughness = base().color.a
+	return (m.color * 2.0).rgb
+}
+"
+);
+
+should_pass_compilation!(
+	arrays,
+	r"
+uniform Float[5] weights
+
+struct Light {
+	Vec3[2] positions,
+	Float power
+}
+
+Float[3] offsets(Float scale) {
+	return [-scale, 0.0, scale]
+}
+
+Float sum(Float[5] values) {
+	let mut total = 0.0
+	for i=0 to length(values) {
+		total = total + values[i]
+	}
+	return total
+}
+
+Float main() {
+	let mut lights = [Light { positions: [Vec3(0.0, 1.0, 0.0), Vec3(1.0, 0.0, 0.0)], power: 2.0 }]
+	lights[0].positions[1].y = offsets(2.0)[2]
+	return sum(weights) * lights[0].power + lights[0].positions[1].y
+}
+"
+);
+
+should_pass_compilation!(
+	overloads,
+	r"
+Float luminance(Vec3 c) {
+	return dot(c, Vec3(0.2126, 0.7152, 0.0722))
+}
+
+Float luminance(Vec4 c) {
+	return luminance(c.rgb) * c.a
+}
+
+Float main() {
+	return luminance(Vec4(1.0, 1.0, 1.0, 0.5))
+}
+"
+);
+
+// parameters may shadow globals, and the variable of a finished loop can be reused
+should_pass_compilation!(
+	shadowing,
+	r"
+in Float x
+
+Float twice(Float x) {
+	return x * 2.0
+}
+
+Float main() {
+	let mut a = twice(x)
+	for i=0 to 2 {
+		a = a + 1.0
+	}
+	for i=0 to 3 {
+		a = a * 2.0
+	}
+	return a
+}
+"
+);
+
+// variables of sibling blocks don't conflict
+should_pass_compilation!(
+	sibling_blocks,
+	r"
+Float main() {
+	let mut a = 0.0
+	if a < 1.0 {
+		let b = 1.0
+		a = b
+	} else {
+		let b = 2
+		a = 2.0
+	}
+	for i=0 to 2 {
+		let b = Vec2(a, a)
+		a = b.x
+	}
+	return a
+}
+"
+);
+
+// structs can contain structs declared after them, and be passed to and returned from functions
+should_pass_compilation!(
+	nested_structs,
+	r"
+struct Ray {
+	Vec3 origin,
+	Vec3 direction
+}
+
+struct Hit {
+	Ray ray,
+	Float[2] distances
+}
+
+Hit trace(Ray ray) {
+	return Hit { ray: ray, distances: [1.0, 2.0] }
+}
+
+Bool main() {
+	let ray = Ray { origin: Vec3(0.0), direction: Vec3(0.0, 0.0, 1.0) }
+	let hit = trace(ray)
+	return hit.ray == ray && hit.ray.direction.z == hit.distances[0]
+}
+"
+);
+
+should_pass_compilation!(
+	returns_on_every_path,
+	r"
+uniform Float x
+
+Float sign_of(Float v) {
+	if v > 0.0 {
+		return 1.0
+	} else if v < 0.0 {
+		return -1.0
+	} else {
+		return 0.0
+	}
+}
+
+Int first_above(Float limit) {
+	let mut i = 0
+	while true {
+		if Float(i) > limit {
+			return i
+		}
+		i = i + 1
+	}
+}
+
+Float main() {
+	return sign_of(x) + Float(first_above(x))
+}
+"
+);
+
+#[test]
+pub fn conversions_are_visible() {
+	let mut program = parser::parse("Vec2 main() {\n\treturn Vec2(1.0, 2.0) * 2\n}").unwrap();
+	compiler::resolve_types::resolve(&mut program, &mut compiler::program_data::ProgramData::new()).unwrap();
+
+	match &program.functions[0].statements[0] {
+		Statement::Return(_, Some(Expr::FuncCall((_, args)))) => {
+			assert!(matches!(*args[1], Expr::Conversion(_, TypeKind::F32)), "{:?}", args[1]);
+		}
+		s => panic!("unexpected statement {:?}", s),
+	}
+	assert!(glsl::generate_glsl(program).contains("vec2(1.0, 2.0) * float(2)"));
+}
+
+#[test]
+pub fn overloads_with_arrays_in_glsl() {
+	let source = r"
+struct Float3 {
+	Float x
+}
+
+Float total(Float[3] v) {
+	return v[0] + v[1] + v[2]
+}
+
+Float total(Float3 v) {
+	return v.x
+}
+
+Float main() {
+	return total([1.0, 2.0, 3.0]) + total(Float3 { x: 4.0 })
+}";
+	let mut program = parser::parse(source).unwrap();
+	compiler::resolve_types::resolve(&mut program, &mut compiler::program_data::ProgramData::new()).unwrap();
+
+	let glsl = glsl::generate_glsl(program);
+	assert!(glsl.contains("float total_Float_arr3(float[3] v) {"), "{}", glsl);
+	assert!(glsl.contains("float total_Float3(Float3 v) {"), "{}", glsl);
+	assert!(glsl.contains("total_Float_arr3(float[3](1.0, 2.0, 3.0)) + total_Float3(Float3(4.0))"), "{}", glsl);
+}
+
+#[test]
+pub fn loops_in_glsl() {
+	let source = r"
+Int main() {
+	let mut sum = 0
+	for i = 0 to 4 {
+		for j = 8 to 0 step -2 {
+			sum = sum + j
+		}
+	}
+	while sum > 10 {
+		sum = sum - 3
+		if sum == 5 {
+			break
+		}
+	}
+	return sum
+}";
+	let mut program = parser::parse(source).unwrap();
+	compiler::resolve_types::resolve(&mut program, &mut compiler::program_data::ProgramData::new()).unwrap();
+
+	let glsl = glsl::generate_glsl(program);
+	assert!(glsl.contains("for (int i = 0; i < 4; i++) {"), "{}", glsl);
+	assert!(glsl.contains("for (int j = 8; j > 0; j += -2) {"), "{}", glsl);
+	assert!(glsl.contains("while (sum > 10) {"), "{}", glsl);
+	assert!(glsl.contains("break;"), "{}", glsl);
+}
+
+#[test]
+pub fn void_functions_in_glsl() {
+	let source = r"
+Void check(Float x) {
+	if x > 1.0 {
+		return
+	}
+}
+
+Float main() {
+	check(2.0)
+	return 1.0
+}";
+	let mut program = parser::parse(source).unwrap();
+	compiler::resolve_types::resolve(&mut program, &mut compiler::program_data::ProgramData::new()).unwrap();
+
+	let glsl = glsl::generate_glsl(program);
+	assert!(glsl.contains("void check(float x) {"), "{}", glsl);
+	assert!(glsl.contains("return;"), "{}", glsl);
+	assert!(glsl.contains("\tcheck(2.0);"), "{}", glsl);
+}
+
+#[test]
+pub fn warns_about_unreachable_code() {
+	let source = r"
+Float main() {
+	let mut a = 1.0
+	for i = 0 to 4 {
+		break
+		a = a + 1.0
+	}
+	return a
+	a = 2.0
+}";
+	let mut program = parser::parse(source).unwrap();
+	let mut data = compiler::program_data::ProgramData::new();
+	compiler::resolve_types::resolve(&mut program, &mut data).unwrap();
+
+	let lines = data
+		.warnings
+		.iter()
+		.map(|w| match w {
+			compiler::control_flow::Warning::UnreachableCode(span, jump) => (span.from.line, jump.from.line),
+		})
+		.collect::<Vec<_>>();
+	assert_eq!(lines, vec![(6, 5), (9, 8)]);
+}
@@ -0,0 +1,73 @@
+use motokigo::{compiler, diagnostic::Diagnostic, parser};
+
+#[test]
+pub fn render_parsing_error() {
+	let source = "Float main() {\n\treturn (1.0 + 2.0\n}\n";
+
+	let error = parser::parse(source).unwrap_err().remove(0);
+	let rendered = Diagnostic::from(error).render(source);
+
+	assert_eq!(
+		rendered,
+		"error[E0202]: expected `)`, found `}`\n --> 3:1\n  |\n3 | }\n  | ^ expected `)`\n"
+	);
+}
+
+#[test]
+pub fn render_type_error() {
+	let source = "Float main() {\n\tlet a = 1.0\n\treturn b\n}\n";
+
+	let mut program = parser::parse(source).unwrap();
+	let error = compiler::resolve_types::resolve(&mut program, &mut compiler::program_data::ProgramData::new())
+		.unwrap_err()
+		.remove(0);
+	let rendered = Diagnostic::from(error).render(source);
+
+	assert_eq!(
+		rendered,
+		"error[E0301]: unknown symbol `b`\n --> 3:9\n  |\n3 | \treturn b\n  | \t       ^ not found in this scope\n"
+	);
+}
+
+#[test]
+pub fn render_redefinition() {
+	let source = "Float main() {\n\tlet a = 1.0\n\tlet a = 2.0\n\treturn a\n}\n";
+
+	let mut program = parser::parse(source).unwrap();
+	let error = compiler::resolve_types::resolve(&mut program, &mut compiler::program_data::ProgramData::new())
+		.unwrap_err()
+		.remove(0);
+	let rendered = Diagnostic::from(error).render(source);
+
+	assert!(rendered.starts_with("error[E0314]: the variable `a` is defined multiple times\n --> 3:6\n"), "{}", rendered);
+	assert!(rendered.contains("previous definition here"), "{}", rendered);
+}
+
+#[test]
+pub fn render_recursion() {
+	let source = "Float a() {\n\treturn b()\n}\n\nFloat b() {\n\treturn a()\n}\n";
+
+	let mut program = parser::parse(source).unwrap();
+	let error = compiler::resolve_types::resolve(&mut program, &mut compiler::program_data::ProgramData::new())
+		.unwrap_err()
+		.remove(0);
+	let rendered = Diagnostic::from(error).render(source);
+
+	assert!(rendered.starts_with("error[E0317]: recursive call to `a`\n --> 6:9\n"), "{}", rendered);
+	assert!(rendered.contains("call cycle: `a()` -> `b()` -> `a()`"), "{}", rendered);
+}
+
+#[test]
+pub fn render_missing_return() {
+	let source = "Float f(Bool b) {\n\tif b {\n\t\treturn 1.0\n\t}\n}\n";
+
+	let mut program = parser::parse(source).unwrap();
+	let error = compiler::resolve_types::resolve(&mut program, &mut compiler::program_data::ProgramData::new())
+		.unwrap_err()
+		.remove(0);
+	let rendered = Diagnostic::from(error).render(source);
+
+	let expected = "error[E0322]: function `f` does not return a value on every path\n --> 1:7\n";
+	assert!(rendered.starts_with(expected), "{}", rendered);
+	assert!(rendered.contains("expected `Float` because of this"), "{}", rendered);
+}
@@ -9,7 +9,11 @@
 
 const TEST_ITERATIONS: usize = 50;
 
-use motokigo::{compiler, glsl, parser, vm::VMState};
+use motokigo::{
+	builtins::{Bool, Filter, Float, IVec3, SamplerState, Texture, Vec2, Vec3, Vec4, Wrap},
+	compiler, glsl, parser,
+	vm::{VMError, VMState},
+};
 
 macro_rules! generate_basic_op_test {
 	($name: ident, $tl: expr, $tr: ty, $op: expr, $opr:expr, $epsilon: expr) => {
@@ -31,7 +35,7 @@ macro_rules! generate_basic_op_test {
 				// check that glsl compiler atleast works
 				glsl::generate_glsl(program.clone());
 
-				let program = compiler::compile(program);
+				let program = compiler::compile(program).unwrap();
 
 				for _ in 0..TEST_ITERATIONS {
 					let mut vm = motokigo::vm::VirtualMachine::new(&program);
@@ -55,6 +59,15 @@ macro_rules! generate_basic_op_test {
 	};
 }
 
+/// Integer division and remainder like the VM does them, which results in zero for a zero divisor.
+fn checked_int_op(op: fn(i32, i32) -> i32) -> impl Fn(i32, i32) -> i32 {
+	move |a, b| if b == 0 { 0 } else { op(a, b) }
+}
+
+fn checked_uint_op(op: fn(u32, u32) -> u32) -> impl Fn(u32, u32) -> u32 {
+	move |a, b| if b == 0 { 0 } else { op(a, b) }
+}
+
 const EPSILON_F32: f32 = 0.000001;
 const EPSILON_I32: i32 = 0;
 
@@ -66,4 +79,868 @@ generate_basic_op_test!(div_float, "Float", f32, "/", std::ops::Div::div, EPSILO
 generate_basic_op_test!(add_int, "Int", i32, "+", i32::wrapping_add, EPSILON_I32);
 generate_basic_op_test!(sub_int, "Int", i32, "-", i32::wrapping_sub, EPSILON_I32);
 generate_basic_op_test!(mul_int, "Int", i32, "*", i32::wrapping_mul, EPSILON_I32);
-generate_basic_op_test!(div_int, "Int", i32, "/", i32::wrapping_div, EPSILON_I32);
+generate_basic_op_test!(div_int, "Int", i32, "/", checked_int_op(i32::wrapping_div), EPSILON_I32);
+
+macro_rules! generate_exact_op_test {
+	($name: ident, $tl: expr, $tr: ty, $op: expr, $opr:expr) => {
+		paste::item! {
+			#[test]
+			pub fn [<exact_op_ $name>]() {
+				let test_source = format!(r"
+                    in {t} a
+                    in {t} b
+
+                    {t} main() {{
+                        return a {o} b
+                    }}
+                ", t=$tl, o=$op);
+
+				let mut program = parser::parse(test_source).unwrap();
+				compiler::resolve_types::resolve(&mut program, &mut compiler::program_data::ProgramData::new()).unwrap();
+
+				// check that glsl compiler atleast works
+				glsl::generate_glsl(program.clone());
+
+				let program = compiler::compile(program).unwrap();
+
+				for _ in 0..TEST_ITERATIONS {
+					let mut vm = motokigo::vm::VirtualMachine::new(&program);
+					let a = rand::random::<$tr>();
+					let b = rand::random::<$tr>();
+
+					vm.set_global("a", a);
+					vm.set_global("b", b);
+
+					if let VMState::VMRunFinished(mut s) = vm.run_fn("main", vec![]) {
+						let r: $tr = unsafe { s.0.pop_stack() };
+						let expected: $tr = ($opr)(a, b);
+
+						assert_eq!(expected, r);
+					} else{
+						panic!("Encountered a breakpoint in a test. Cursed.");
+					}
+				}
+			}
+		}
+	};
+}
+
+generate_exact_op_test!(add_uint, "UInt", u32, "+", u32::wrapping_add);
+generate_exact_op_test!(sub_uint, "UInt", u32, "-", u32::wrapping_sub);
+generate_exact_op_test!(mul_uint, "UInt", u32, "*", u32::wrapping_mul);
+generate_exact_op_test!(div_uint, "UInt", u32, "/", checked_uint_op(u32::wrapping_div));
+generate_exact_op_test!(mod_uint, "UInt", u32, "%", checked_uint_op(u32::wrapping_rem));
+generate_exact_op_test!(mod_int, "Int", i32, "%", checked_int_op(i32::wrapping_rem));
+
+generate_exact_op_test!(bitand_int, "Int", i32, "&", std::ops::BitAnd::bitand);
+generate_exact_op_test!(bitor_int, "Int", i32, "|", std::ops::BitOr::bitor);
+generate_exact_op_test!(bitxor_int, "Int", i32, "^", std::ops::BitXor::bitxor);
+generate_exact_op_test!(shl_int, "Int", i32, "<<", |a: i32, b: i32| a.wrapping_shl(b as u32));
+generate_exact_op_test!(shr_int, "Int", i32, ">>", |a: i32, b: i32| a.wrapping_shr(b as u32));
+generate_exact_op_test!(bitand_uint, "UInt", u32, "&", std::ops::BitAnd::bitand);
+generate_exact_op_test!(bitor_uint, "UInt", u32, "|", std::ops::BitOr::bitor);
+generate_exact_op_test!(bitxor_uint, "UInt", u32, "^", std::ops::BitXor::bitxor);
+generate_exact_op_test!(shl_uint, "UInt", u32, "<<", u32::wrapping_shl);
+generate_exact_op_test!(shr_uint, "UInt", u32, ">>", u32::wrapping_shr);
+
+#[test]
+pub fn integer_division_by_zero() {
+	let source = r"
+in Int b
+in UInt c
+
+Vec4 main() {
+	return Vec4(Float(7 / b), Float(-7 % b), Float(7u / c), Float(7u % c))
+}
+";
+	let program = compiler::compile(parser::parse(source).unwrap()).unwrap();
+
+	let mut vm = motokigo::vm::VirtualMachine::new(&program);
+	vm.set_global("b", 0i32);
+	vm.set_global("c", 0u32);
+	match vm.run_fn("main", vec![]) {
+		VMState::VMRunFinished(mut s) => assert_eq!(unsafe { s.0.pop_stack::<Vec4>() }, Vec4::new(0.0, 0.0, 0.0, 0.0)),
+		_ => panic!("expected the program to finish"),
+	}
+}
+
+#[test]
+pub fn integer_vector_division_by_zero() {
+	let source = r"
+in Int b
+in UInt c
+
+IVec3 main() {
+	let scalar = IVec3(7, -7, 1) / b
+	let components = IVec3(7, -7, 8) / IVec3(b, 1, 2)
+	let unsigned = UVec2(7u, 9u) / UVec2(c, 3u) + UVec2(5u) / c
+	return scalar + components + IVec3(Int(unsigned.x), Int(unsigned.y), 0)
+}
+";
+	let program = compiler::compile(parser::parse(source).unwrap()).unwrap();
+
+	let mut vm = motokigo::vm::VirtualMachine::new(&program);
+	vm.set_global("b", 0i32);
+	vm.set_global("c", 0u32);
+	match vm.run_fn("main", vec![]) {
+		VMState::VMRunFinished(mut s) => assert_eq!(unsafe { s.0.pop_stack::<IVec3>() }, IVec3::new(0, -4, 4)),
+		_ => panic!("expected the program to finish"),
+	}
+}
+
+#[test]
+pub fn operator_precedence() {
+	let program = parser::parse(
+		r"
+Int main() {
+	let a = 1 + 2 * 3 << 1
+	let b = 6 & 3 | 8
+	let c = 1 | 2 ^ 3 & 1
+	let d = 7 % 4 + ~0
+	return a * 1000000 + b * 10000 + c * 100 + d
+}
+",
+	)
+	.unwrap();
+	let program = compiler::compile(program).unwrap();
+
+	let vm = motokigo::vm::VirtualMachine::new(&program);
+	if let VMState::VMRunFinished(mut s) = vm.run_fn("main", vec![]) {
+		let r: i32 = unsafe { s.0.pop_stack() };
+		assert_eq!(r, 14_10_03_02);
+	} else {
+		panic!("Encountered a breakpoint in a test. Cursed.");
+	}
+}
+
+macro_rules! generate_logic_op_test {
+	($name: ident, $op: expr, $opr: expr) => {
+		paste::item! {
+			#[test]
+			pub fn [<logic_op_ $name>]() {
+				let test_source = format!(r"
+                    in Bool a
+                    in Bool b
+
+                    Bool main() {{
+                        return a {o} b
+                    }}
+                ", o=$op);
+
+				let mut program = parser::parse(test_source).unwrap();
+				compiler::resolve_types::resolve(&mut program, &mut compiler::program_data::ProgramData::new()).unwrap();
+
+				// check that glsl compiler atleast works
+				glsl::generate_glsl(program.clone());
+
+				let program = compiler::compile(program).unwrap();
+
+				for &(a, b) in &[(false, false), (false, true), (true, false), (true, true)] {
+					let mut vm = motokigo::vm::VirtualMachine::new(&program);
+
+					vm.set_global("a", Bool::from(a));
+					vm.set_global("b", Bool::from(b));
+
+					if let VMState::VMRunFinished(mut s) = vm.run_fn("main", vec![]) {
+						let r: Bool = unsafe { s.0.pop_stack() };
+						assert_eq!(bool::from(r), ($opr)(a, b), "{} {} {}", a, $op, b);
+					} else {
+						panic!("Encountered a breakpoint in a test. Cursed.");
+					}
+				}
+			}
+		}
+	};
+}
+
+generate_logic_op_test!(and, "&&", |a: bool, b: bool| a && b);
+generate_logic_op_test!(or, "||", |a: bool, b: bool| a || b);
+generate_logic_op_test!(xor, "^^", |a: bool, b: bool| a != b);
+generate_logic_op_test!(equality, "==", |a: bool, b: bool| a == b);
+generate_logic_op_test!(not_equal, "!=", |a: bool, b: bool| a != b);
+
+#[test]
+pub fn logic_ops_short_circuit() {
+	let program = parser::parse(
+		r"
+in Int zero
+
+Bool main() {
+	let a = false && 1 / zero == 1
+	let b = true || 1 / zero == 1
+	return !a && b
+}
+",
+	)
+	.unwrap();
+	let program = compiler::compile(program).unwrap();
+
+	let mut vm = motokigo::vm::VirtualMachine::new(&program);
+	vm.set_global("zero", 0);
+
+	if let VMState::VMRunFinished(mut s) = vm.run_fn("main", vec![]) {
+		let r: Bool = unsafe { s.0.pop_stack() };
+		assert!(bool::from(r));
+	} else {
+		panic!("Encountered a breakpoint in a test. Cursed.");
+	}
+}
+
+#[test]
+pub fn integer_and_bool_vectors() {
+	let program = parser::parse(
+		r"
+IVec3 main() {
+	let a = IVec3(Vec3(1.5, -2.5, 3.0))
+	let b = UVec2(3u, 4u) * 2u
+	let c = all(lessThan(IVec2(1, 2), IVec2(2, 3))) && any(BVec2(false, true))
+	if c && b == UVec2(6u, 8u) {
+		return a + IVec3(10, 10, 10)
+	}
+	return -a
+}
+",
+	)
+	.unwrap();
+	let program = compiler::compile(program).unwrap();
+
+	let vm = motokigo::vm::VirtualMachine::new(&program);
+	if let VMState::VMRunFinished(mut s) = vm.run_fn("main", vec![]) {
+		let r: IVec3 = unsafe { s.0.pop_stack() };
+		assert_eq!(r.to_arr(), [11, 8, 13]);
+	} else {
+		panic!("Encountered a breakpoint in a test. Cursed.");
+	}
+}
+
+#[test]
+pub fn matrix_arithmetic() {
+	let program = parser::parse(
+		r"
+Float main() {
+	let m = Mat2(1.0, 2.0, 3.0, 4.0)
+	let r = Mat2x3(1.0, 2.0, 3.0, 4.0, 5.0, 6.0)
+	let v = Vec2(1.0, -1.0)
+
+	let mut error = distance(m * Vec2(1.0, 1.0), Vec2(4.0, 6.0))
+	error = error + distance(Vec2(1.0, 1.0) * m, Vec2(3.0, 7.0))
+	error = error + abs(determinant(m) - (-2.0))
+	error = error + distance(inverse(m) * m * v, v)
+	error = error + distance(r * Vec2(1.0, 1.0), Vec3(5.0, 7.0, 9.0))
+	error = error + distance((r * m) * v, r * (m * v))
+	error = error + distance(transpose(r) * Vec3(1.0, 1.0, 1.0), Vec2(6.0, 15.0))
+	error = error + abs(determinant(Mat3(2.0, 0.0, 0.0, 0.0, 3.0, 0.0, 1.0, 0.0, 4.0)) - 24.0)
+	return error
+}
+",
+	)
+	.unwrap();
+	let program = compiler::compile(program).unwrap();
+
+	let vm = motokigo::vm::VirtualMachine::new(&program);
+	if let VMState::VMRunFinished(mut s) = vm.run_fn("main", vec![]) {
+		let r: f32 = unsafe { s.0.pop_stack() };
+		assert!(r.abs() <= EPSILON_F32 * 10.0, "accumulated error {}", r);
+	} else {
+		panic!("Encountered a breakpoint in a test. Cursed.");
+	}
+}
+
+fn run_main<T: bytemuck::Pod + std::fmt::Debug>(source: &str) -> T {
+	let program = compiler::compile(parser::parse(source).unwrap()).unwrap();
+
+	let vm = motokigo::vm::VirtualMachine::new(&program);
+	if let VMState::VMRunFinished(mut s) = vm.run_fn("main", vec![]) {
+		unsafe { s.0.pop_stack() }
+	} else {
+		panic!("Encountered a breakpoint in a test. Cursed.");
+	}
+}
+
+macro_rules! generate_stdlib_test {
+	($name: ident, $tl: expr, $expr: expr, $tr: ty, $expected: expr) => {
+		paste::item! {
+			#[test]
+			pub fn [<stdlib_ $name>]() {
+				let source = format!("{} main() {{\n\treturn {}\n}}", $tl, $expr);
+				let r: $tr = run_main(&source);
+				let expected: &[f32] = &$expected;
+
+				let r = bytemuck::cast_slice::<_, f32>(std::slice::from_ref(&r));
+				for (r, e) in r.iter().zip(expected.iter()) {
+					assert!((r - e).abs() <= EPSILON_F32, "{}: got {:?}, expected {:?}", $expr, r, expected);
+				}
+			}
+		}
+	};
+}
+
+generate_stdlib_test!(cross, "Vec3", "cross(Vec3(1.0, 0.0, 0.0), Vec3(0.0, 1.0, 0.0))", Vec3, [0.0, 0.0, 1.0]);
+generate_stdlib_test!(reflect, "Vec2", "reflect(Vec2(1.0, -1.0), Vec2(0.0, 1.0))", Vec2, [1.0, 1.0]);
+generate_stdlib_test!(refract, "Vec2", "refract(Vec2(0.0, -1.0), Vec2(0.0, 1.0), 1.0)", Vec2, [0.0, -1.0]);
+generate_stdlib_test!(refract_total_internal_reflection, "Vec2", "refract(normalize(Vec2(1.0, -0.1)), Vec2(0.0, 1.0), 1.5)", Vec2, [0.0, 0.0]);
+generate_stdlib_test!(faceforward, "Vec2", "faceforward(Vec2(0.0, 1.0), Vec2(0.0, 1.0), Vec2(0.0, 1.0))", Vec2, [0.0, -1.0]);
+generate_stdlib_test!(mix, "Vec2", "mix(Vec2(0.0, 10.0), Vec2(10.0, 20.0), 0.25)", Vec2, [2.5, 12.5]);
+generate_stdlib_test!(mix_vec, "Vec2", "mix(Vec2(0.0, 10.0), Vec2(10.0, 20.0), Vec2(0.5, 1.0))", Vec2, [5.0, 20.0]);
+generate_stdlib_test!(clamp, "Vec3", "clamp(Vec3(-1.0, 0.5, 2.0), 0.0, 1.0)", Vec3, [0.0, 0.5, 1.0]);
+generate_stdlib_test!(clamp_float, "Float", "clamp(5.0, 0.0, 2.0)", Float, [2.0]);
+generate_stdlib_test!(step, "Vec2", "step(0.5, Vec2(0.2, 0.7))", Vec2, [0.0, 1.0]);
+generate_stdlib_test!(smoothstep, "Float", "smoothstep(0.0, 1.0, 0.25)", Float, [0.15625]);
+generate_stdlib_test!(smoothstep_vec, "Vec3", "smoothstep(0.0, 2.0, Vec3(-1.0, 1.0, 3.0))", Vec3, [0.0, 0.5, 1.0]);
+generate_stdlib_test!(mod_negative, "Float", "mod(-1.5, 1.0)", Float, [0.5]);
+generate_stdlib_test!(mod_vec, "Vec2", "mod(Vec2(5.5, -0.5), 2.0)", Vec2, [1.5, 1.5]);
+generate_stdlib_test!(abs_vec, "Vec2", "abs(Vec2(-1.0, 2.0))", Vec2, [1.0, 2.0]);
+generate_stdlib_test!(floor_vec, "Vec2", "floor(Vec2(-0.5, 1.5))", Vec2, [-1.0, 1.0]);
+generate_stdlib_test!(min_vec, "Vec2", "min(Vec2(1.0, 5.0), Vec2(3.0, 2.0))", Vec2, [1.0, 2.0]);
+generate_stdlib_test!(max_vec_float, "Vec2", "max(Vec2(1.0, 5.0), 3.0)", Vec2, [3.0, 5.0]);
+generate_stdlib_test!(inversesqrt, "Float", "inversesqrt(4.0)", Float, [0.5]);
+generate_stdlib_test!(fma, "Vec2", "fma(Vec2(2.0, 1.0), Vec2(3.0, 1.0), Vec2(4.0, 1.0))", Vec2, [10.0, 2.0]);
+generate_stdlib_test!(fract_negative, "Float", "fract(-0.25)", Float, [0.75]);
+generate_stdlib_test!(sign_zero, "Vec3", "sign(Vec3(-2.0, 0.0, 3.0))", Vec3, [-1.0, 0.0, 1.0]);
+
+generate_stdlib_test!(cast_float, "Float", "Float(3) + Float(Int(2.7)) + Float(true)", Float, [6.0]);
+generate_stdlib_test!(cast_vec_splat, "Vec3", "Vec3(2.0)", Vec3, [2.0, 2.0, 2.0]);
+generate_stdlib_test!(cast_vec_truncate, "Vec2", "Vec2(Vec4(1.0, 2.0, 3.0, 4.0))", Vec2, [1.0, 2.0]);
+generate_stdlib_test!(cast_mat_diagonal, "Mat2x3", "Mat2x3(2.0)", motokigo::builtins::Mat2x3, [2.0, 0.0, 0.0, 0.0, 2.0, 0.0]);
+generate_stdlib_test!(cast_mat_resize, "Mat3", "Mat3(Mat2(1.0, 2.0, 3.0, 4.0))", motokigo::builtins::Mat3, [1.0, 2.0, 0.0, 3.0, 4.0, 0.0, 0.0, 0.0, 1.0]);
+generate_stdlib_test!(implicit_int_to_float, "Float", "10.0 / 4 + max(1, 0.5)", Float, [3.5]);
+generate_stdlib_test!(implicit_int_vector, "Vec2", "2 * Vec2(1.0, 2.0) + IVec2(1, 1)", Vec2, [3.0, 5.0]);
+generate_stdlib_test!(implicit_broadcast, "Vec3", "1.0 / Vec3(1.0, 2.0, 4.0) - 1", Vec3, [0.0, -0.5, -0.75]);
+generate_stdlib_test!(implicit_constructor_args, "Vec3", "Vec3(1, 2.5, 3u)", Vec3, [1.0, 2.5, 3.0]);
+
+#[test]
+pub fn swizzle_writes() {
+	let r: Vec4 = run_main(
+		r"
+Vec4 main() {
+	let mut c = Vec4(1.0, 2.0, 3.0, 4.0)
+	c.zx = Vec2(10.0, 30.0)
+	c.w = normalize(Vec3(0.0, 0.0, 2.0)).z + c.x
+	return c
+}
+",
+	);
+
+	assert_eq!(r, Vec4::new(30.0, 2.0, 10.0, 31.0));
+}
+
+#[test]
+pub fn nested_field_access() {
+	let r: Vec3 = run_main(
+		r"
+struct Light {
+	Float power,
+	Vec3 dir
+}
+
+Light make() {
+	return Light { power: 0.5, dir: Vec3(7.0, 8.0, 9.0) }
+}
+
+Vec3 main() {
+	let mut l = Light { power: 2.0, dir: Vec3(1.0, 2.0, 3.0) }
+	l.dir.yz = l.dir.zy * l.power
+	return l.dir.zyx + make().dir.xxy * make().power
+}
+",
+	);
+
+	assert_eq!(r, Vec3::new(7.5, 9.5, 5.0));
+}
+
+#[test]
+pub fn array_indexing() {
+	let program = parser::parse(
+		r"
+uniform Float[4] weights
+
+struct Light {
+	Vec2[2] corners,
+	Float power
+}
+
+Float[3] offsets() {
+	return [1.0, 10.0, 100.0]
+}
+
+Vec4 main() {
+	let mut lights = [
+		Light { corners: [Vec2(1.0, 2.0), Vec2(3.0, 4.0)], power: 1.0 },
+		Light { corners: [Vec2(5.0, 6.0), Vec2(7.0, 8.0)], power: 2.0 },
+	]
+	let mut total = 0.0
+	for i=0 to length(weights) {
+		total = total + weights[i]
+	}
+	for i=0 to 2 {
+		lights[i].corners[1].y = lights[i].corners[0].x * lights[i].power
+	}
+	let j = 1
+	return Vec4(total, lights[1].corners[j].y, lights[0].corners[1].y, offsets()[j + 1] + [Vec2(0.5, 0.25)][0].y)
+}
+",
+	)
+	.unwrap();
+	let program = compiler::compile(program).unwrap();
+
+	let mut vm = motokigo::vm::VirtualMachine::new(&program);
+	vm.set_global("weights", [1.0f32, 2.0, 3.0, 4.0]);
+	if let VMState::VMRunFinished(mut s) = vm.run_fn("main", vec![]) {
+		let r: Vec4 = unsafe { s.0.pop_stack() };
+		assert_eq!(r, Vec4::new(10.0, 10.0, 1.0, 100.25));
+	} else {
+		panic!("Encountered a breakpoint in a test. Cursed.");
+	}
+}
+
+#[test]
+pub fn array_index_out_of_bounds() {
+	let program = parser::parse(
+		r"
+Float main() {
+	let a = [1.0, 2.0, 3.0]
+	let i = 0 - 1
+	return a[i]
+}
+",
+	)
+	.unwrap();
+	let program = compiler::compile(program).unwrap();
+
+	let vm = motokigo::vm::VirtualMachine::new(&program);
+	match vm.run_fn("main", vec![]) {
+		VMState::Error(s) => assert_eq!(s.1, VMError::IndexOutOfBounds { index: -1i32 as u32, len: 3 }),
+		_ => panic!("expected the out of bounds index to abort the program"),
+	}
+}
+
+#[test]
+pub fn function_overloading() {
+	let r: Vec3 = run_main(
+		r"
+Float scale(Float x) {
+	return x * 2.0
+}
+
+Float scale(Float x, Float by) {
+	return x * by
+}
+
+Vec3 scale(Vec3 v) {
+	return v * 3.0
+}
+
+Vec3 main() {
+	return scale(Vec3(1.0, 1.0, 1.0)) + Vec3(scale(1.0), scale(1.0, 5.0), 0.0)
+}
+",
+	);
+
+	assert_eq!(r, Vec3::new(5.0, 8.0, 3.0));
+}
+
+#[test]
+pub fn block_scoping() {
+	let r: Vec3 = run_main(
+		r"
+Float pick(Int i) {
+	let mut r = 0.0
+	if i == 0 {
+		let a = 1.0
+		r = a
+	} else if i == 1 {
+		let b = Vec2(2.0, 3.0)
+		r = b.y
+	} else {
+		r = 4.0
+	}
+	return r
+}
+
+Vec3 main() {
+	let mut sum = 0.0
+	for i=0 to 4 {
+		let x = Vec2(1.0, 2.0)
+		for j=0 to 2 {
+			let y = x.y
+			sum = sum + y
+		}
+	}
+	return Vec3(sum, pick(0) + pick(1), pick(2))
+}
+",
+	);
+
+	assert_eq!(r, Vec3::new(16.0, 4.0, 4.0));
+}
+
+#[test]
+pub fn call_later_function() {
+	let r: Float = run_main(
+		r"
+Float main() {
+	return double(half(4.0))
+}
+
+Float double(Float x) {
+	return half(x) * 4.0
+}
+
+Float half(Float x) {
+	return x / 2.0
+}
+",
+	);
+
+	assert_eq!(r, 4.0);
+}
+
+#[test]
+pub fn implicit_conversion_of_arguments() {
+	let r: Float = run_main(
+		r"
+Float half(Float x) {
+	return x / 2.0
+}
+
+Float main() {
+	let i = 5
+	return half(i)
+}
+",
+	);
+
+	assert_eq!(r, 2.5);
+}
+
+#[test]
+pub fn struct_outputs() {
+	let source = r"
+struct Output {
+	Vec4 color,
+	Float[2] weights,
+	Float depth
+}
+
+Output main() {
+	return Output { color: Vec4(1.0, 0.5, 0.25, 1.0), weights: [0.25, 0.75], depth: 0.5 }
+}
+";
+	let program = compiler::compile(parser::parse(source).unwrap()).unwrap();
+
+	let vm = motokigo::vm::VirtualMachine::new(&program);
+	let s = match vm.run_fn("main", vec![]) {
+		VMState::VMRunFinished(s) => s,
+		_ => panic!("expected the program to finish"),
+	};
+
+	let names = s.outputs().into_iter().map(|(name, _)| name).collect::<Vec<_>>();
+	assert_eq!(names, vec!["color", "weights", "depth"]);
+	assert_eq!(s.get_output::<Vec4>("color"), Some(Vec4::new(1.0, 0.5, 0.25, 1.0)));
+	assert_eq!(s.get_output::<Float>("depth"), Some(0.5));
+	assert_eq!(s.get_output::<Float>("missing"), None);
+	assert_eq!(s.get_output::<Vec4>("depth"), None);
+
+	let mut ast = parser::parse(source).unwrap();
+	compiler::resolve_types::resolve(&mut ast, &mut compiler::program_data::ProgramData::new()).unwrap();
+	let glsl = glsl::generate_glsl(ast);
+	assert!(glsl.contains("layout(location = 0) out vec4 out_color;\n"), "{}", glsl);
+	assert!(glsl.contains("layout(location = 1) out float[2] out_weights;\n"), "{}", glsl);
+	assert!(glsl.contains("layout(location = 3) out float out_depth;\n"), "{}", glsl);
+	assert!(glsl.contains("\tout_depth = rt.depth;\n"), "{}", glsl);
+}
+
+#[test]
+pub fn scalar_output() {
+	let program = compiler::compile(parser::parse("Float main() {\n\treturn 2.0\n}").unwrap()).unwrap();
+
+	let vm = motokigo::vm::VirtualMachine::new(&program);
+	match vm.run_fn("main", vec![]) {
+		VMState::VMRunFinished(s) => assert_eq!(s.get_output::<Float>("out_0"), Some(2.0)),
+		_ => panic!("expected the program to finish"),
+	}
+}
+
+#[test]
+pub fn nested_structs() {
+	let r: Vec4 = run_main(
+		r"
+Vec4 main() {
+	let l = Light { pos: Vec3(1.0, 2.0, 3.0), color: Color { rgb: Vec3(0.5, 0.5, 0.5), weights: [1.0, 2.0], m: Mat2(1.0) } }
+	let mut m = move(l, 1.0)
+	let moved = Float(m == l)
+	m.pos = l.pos
+	let restored = Float(m == l)
+	m.color.weights[1] = 5.0
+	let changed = Float(m != l)
+	return Vec4(moved, restored, changed, m.color.weights[1])
+}
+
+Light move(Light l, Float by) {
+	return Light { pos: l.pos + Vec3(by, by, by), color: l.color }
+}
+
+struct Light {
+	Vec3 pos,
+	Color color
+}
+
+struct Color {
+	Vec3 rgb,
+	Float[2] weights,
+	Mat2 m
+}
+",
+	);
+
+	assert_eq!(r, Vec4::new(0.0, 1.0, 1.0, 5.0));
+}
+
+/// A 2x2 texture with a red, green, blue and white texel, in that order.
+fn checker_texture(sampler: SamplerState) -> Texture {
+	let texels = vec![
+		Vec4::new(1.0, 0.0, 0.0, 1.0),
+		Vec4::new(0.0, 1.0, 0.0, 1.0),
+		Vec4::new(0.0, 0.0, 1.0, 1.0),
+		Vec4::new(1.0, 1.0, 1.0, 1.0),
+	];
+	Texture::new(2, 2, texels).with_sampler(sampler)
+}
+
+/// Runs a program returning `expr`, with `texture` bound to the uniform `tex`.
+fn run_with_texture(expr: &str, texture: Option<Texture>) -> Vec4 {
+	let source = format!("uniform Sampler2D tex\n\nVec4 main() {{\n\treturn {}\n}}", expr);
+	let program = compiler::compile(parser::parse(source).unwrap()).unwrap();
+
+	let mut vm = motokigo::vm::VirtualMachine::new(&program);
+	if let Some(texture) = texture {
+		vm.bind_texture("tex", texture);
+	}
+	match vm.run_fn("main", vec![]) {
+		VMState::VMRunFinished(mut s) => unsafe { s.0.pop_stack() },
+		_ => panic!("expected the program to finish"),
+	}
+}
+
+#[test]
+pub fn texture_filtering() {
+	let nearest = SamplerState { filter: Filter::Nearest, ..SamplerState::default() };
+	let r = run_with_texture("texture(tex, Vec2(0.25, 0.75))", Some(checker_texture(nearest)));
+	assert_eq!(r, Vec4::new(0.0, 0.0, 1.0, 1.0));
+
+	let r = run_with_texture("texture(tex, Vec2(0.5, 0.5))", Some(checker_texture(SamplerState::default())));
+	assert_eq!(r, Vec4::new(0.5, 0.5, 0.5, 1.0));
+
+	let r = run_with_texture("texture(tex, Vec2(0.5, 0.5))", None);
+	assert_eq!(r, Vec4::new(0.0, 0.0, 0.0, 1.0));
+}
+
+#[test]
+pub fn texture_wrapping() {
+	let r = run_with_texture("texture(tex, Vec2(0.0, 0.25))", Some(checker_texture(SamplerState::default())));
+	assert_eq!(r, Vec4::new(0.5, 0.5, 0.0, 1.0));
+
+	let clamp = SamplerState { wrap_s: Wrap::ClampToEdge, wrap_t: Wrap::ClampToEdge, ..SamplerState::default() };
+	let r = run_with_texture("texture(tex, Vec2(0.0, 0.25))", Some(checker_texture(clamp)));
+	assert_eq!(r, Vec4::new(1.0, 0.0, 0.0, 1.0));
+
+	let mirror = SamplerState { filter: Filter::Nearest, wrap_s: Wrap::MirroredRepeat, ..SamplerState::default() };
+	let r = run_with_texture("texture(tex, Vec2(1.25, 0.25))", Some(checker_texture(mirror)));
+	assert_eq!(r, Vec4::new(0.0, 1.0, 0.0, 1.0));
+}
+
+#[test]
+pub fn texture_non_finite_coordinates() {
+	let clamp = SamplerState { filter: Filter::Nearest, wrap_s: Wrap::ClampToEdge, ..SamplerState::default() };
+	let r = run_with_texture("texture(tex, Vec2(1.0 / 0.0, 0.25))", Some(checker_texture(clamp)));
+	assert_eq!(r, Vec4::new(0.0, 1.0, 0.0, 1.0));
+
+	// the filter weights are undefined, but sampling must not overflow the texel coordinates
+	for uv in &["Vec2(1.0 / 0.0, 0.5)", "Vec2(-1.0 / 0.0, 1.0e30)", "Vec2(0.0 / 0.0, 0.5)"] {
+		for wrap in &[Wrap::Repeat, Wrap::MirroredRepeat, Wrap::ClampToEdge] {
+			let sampler = SamplerState { wrap_s: *wrap, wrap_t: *wrap, ..SamplerState::default() };
+			run_with_texture(&format!("texture(tex, {})", uv), Some(checker_texture(sampler)));
+		}
+	}
+}
+
+#[test]
+pub fn texture_mipmaps() {
+	let sampler = SamplerState { filter: Filter::Nearest, mipmap_filter: Some(Filter::Linear), ..SamplerState::default() };
+	let texture = || Some(checker_texture(sampler).with_mipmaps());
+
+	let r = run_with_texture("textureLod(tex, Vec2(0.25, 0.25), 1.0)", texture());
+	assert_eq!(r, Vec4::new(0.5, 0.5, 0.5, 1.0));
+
+	let r = run_with_texture("textureLod(tex, Vec2(0.25, 0.25), 0.5)", texture());
+	assert_eq!(r, Vec4::new(0.75, 0.25, 0.25, 1.0));
+
+	let r = run_with_texture("Vec2(textureSize(tex, 0)).xyxy + Vec2(textureSize(tex, 1)).xyxy * 10.0", texture());
+	assert_eq!(r, Vec4::new(12.0, 12.0, 12.0, 12.0));
+
+	let r = run_with_texture("texelFetch(tex, IVec2(1, 1), 0) + texelFetch(tex, IVec2(2, 0), 0)", texture());
+	assert_eq!(r, Vec4::new(1.0, 1.0, 1.0, 1.0));
+}
+
+#[test]
+pub fn sampler_parameters() {
+	let source = r"
+uniform Sampler2D tex
+
+Vec4 main() {
+	return lookup(tex, Vec2(0.75, 0.25))
+}
+
+Vec4 lookup(Sampler2D s, Vec2 uv) {
+	return texture(s, uv)
+}
+";
+	let program = compiler::compile(parser::parse(source).unwrap()).unwrap();
+
+	let mut vm = motokigo::vm::VirtualMachine::new(&program);
+	let nearest = SamplerState { filter: Filter::Nearest, ..SamplerState::default() };
+	vm.bind_texture("tex", checker_texture(SamplerState::default()));
+	// binding a uniform again replaces its texture
+	vm.bind_texture("tex", checker_texture(nearest));
+	assert_eq!(vm.textures.len(), 1);
+
+	match vm.run_fn("main", vec![]) {
+		VMState::VMRunFinished(mut s) => {
+			let r: Vec4 = unsafe { s.0.pop_stack() };
+			assert_eq!(r, Vec4::new(0.0, 1.0, 0.0, 1.0));
+		}
+		_ => panic!("expected the program to finish"),
+	}
+
+	let mut ast = parser::parse(source).unwrap();
+	compiler::resolve_types::resolve(&mut ast, &mut compiler::program_data::ProgramData::new()).unwrap();
+	let glsl = glsl::generate_glsl(ast);
+	assert!(glsl.contains("uniform sampler2D tex;\n"), "{}", glsl);
+	assert!(glsl.contains("vec4 lookup(sampler2D s, vec2 uv)"), "{}", glsl);
+	assert!(glsl.contains("texture(s, uv)"), "{}", glsl);
+}
+
+#[test]
+pub fn stepped_loops() {
+	let r: Vec3 = run_main(
+		r"
+Vec3 main() {
+	let mut down = 0
+	for i = 10 to 0 step -2 {
+		down = down * 10 + i / 2
+	}
+	let mut floats = 0.0
+	for t = 0.0 to 1.0 step 0.25 {
+		floats = floats + t
+	}
+	let mut up = 0
+	for i = 1 to 10 step 3 {
+		up = up + i
+	}
+	return Vec3(Float(down), floats, Float(up))
+}
+",
+	);
+
+	assert_eq!(r, Vec3::new(54321.0, 1.5, 12.0));
+}
+
+#[test]
+pub fn while_break_continue() {
+	let r: Vec3 = run_main(
+		r"
+Vec3 main() {
+	let mut n = 0
+	let mut odd = 0
+	while true {
+		let k = n
+		n = n + 1
+		if k > 5 {
+			let unused = Vec4(1.0)
+			break
+		}
+		if k % 2 == 0 {
+			continue
+		}
+		odd = odd + k
+	}
+
+	let mut skipped = 0
+	for i = 0 to 5 {
+		let x = Vec2(Float(i))
+		if i == 2 {
+			continue
+		}
+		if i == 4 {
+			break
+		}
+		skipped = skipped + Int(x.y)
+	}
+	return Vec3(Float(n), Float(odd), Float(skipped))
+}
+",
+	);
+
+	assert_eq!(r, Vec3::new(7.0, 9.0, 4.0));
+}
+
+#[test]
+pub fn void_functions_return_implicitly() {
+	let source = r"
+Void main() {
+	let mut a = 1.0
+	for i = 0 to 3 {
+		a = a * 2.0
+	}
+}
+";
+	let program = compiler::compile(parser::parse(source).unwrap()).unwrap();
+
+	let vm = motokigo::vm::VirtualMachine::new(&program);
+	match vm.run_fn("main", vec![]) {
+		VMState::VMRunFinished(s) => {
+			assert!(s.outputs().is_empty());
+			assert_eq!(s.0.stack.len(), program.data.static_section_size);
+		}
+		_ => panic!("expected the program to finish"),
+	}
+
+	let mut ast = parser::parse(source).unwrap();
+	compiler::resolve_types::resolve(&mut ast, &mut compiler::program_data::ProgramData::new()).unwrap();
+	let glsl = glsl::generate_glsl(ast);
+	assert!(glsl.contains("void main() {\n\tm_impl_main();\n}"), "{}", glsl);
+	assert!(!glsl.contains("out_0"), "{}", glsl);
+}
+
+#[test]
+pub fn void_calls_and_bare_returns() {
+	let r: Vec3 = run_main(
+		r"
+Void skip(Int n) {
+	let unused = Vec4(Float(n))
+	if n > 1 {
+		return
+	}
+	let more = Vec3(1.0)
+}
+
+Vec4 discarded(Float x) {
+	return Vec4(x)
+}
+
+Vec3 main() {
+	let mut sum = 0
+	for i = 0 to 4 {
+		skip(i)
+		discarded(Float(i))
+		sum = sum + i
+	}
+	let last = 2.5
+	skip(sum)
+	return Vec3(Float(sum), last, length(Vec2(3.0, 4.0)))
+}
+",
+	);
+
+	assert_eq!(r, Vec3::new(6.0, 2.5, 5.0));
+}
@@ -0,0 +1,147 @@
+use motokigo::{ast::Statement, parser, scanner::Token};
+
+#[test]
+pub fn recover_from_statement_errors() {
+	let (program, errors) = parser::parse_recovering(
+		r"
+Float main() {
+	let a = 1.0 +
+	let b = )
+	let c = 2.0
+	return c
+}
+",
+	);
+
+	assert_eq!(errors.len(), 2);
+	assert_eq!(errors[0].span().unwrap().from.line, 4);
+	assert_eq!(errors[1].span().unwrap().from.line, 4);
+
+	let main = program.get_function("main".to_owned()).unwrap();
+	assert_eq!(main.statements.len(), 2);
+}
+
+#[test]
+pub fn recover_from_top_level_errors() {
+	let (program, errors) = parser::parse_recovering(
+		r"
+in Float
+in Vec3 normal
+
+struct Foo {
+	Float x
+	Float y
+}
+
+Float foo(Float a Float b) {
+	return a
+}
+
+Float main() {
+	if 1 {
+		let a = (1.0
+	}
+	return 1.0
+}
+",
+	);
+
+	assert_eq!(errors.len(), 4);
+	assert_eq!(program.in_parameters.len(), 1);
+	assert!(program.struct_declarations.is_empty());
+	assert!(program.get_function("foo".to_owned()).is_none());
+
+	let main = program.get_function("main".to_owned()).unwrap();
+	assert_eq!(main.statements.len(), 2);
+}
+
+#[test]
+pub fn unterminated_block() {
+	let errors = parser::parse("Float main() {\n\treturn 1.0\n").unwrap_err();
+
+	assert_eq!(errors.len(), 1);
+	assert!(matches!(errors[0], parser::ParsingError::UnexpectedEndOfInput));
+}
+
+#[test]
+pub fn expected_token() {
+	let errors = parser::parse("Float main() {\n\tlet a 1.0\n\treturn a\n}\n").unwrap_err();
+
+	assert_eq!(errors.len(), 1);
+	match &errors[0] {
+		parser::ParsingError::ExpectedToken(Token::Equals, found) => assert_eq!(found.from.line, 2),
+		e => panic!("unexpected error {:?}", e),
+	}
+}
+
+#[test]
+pub fn bare_return_ends_at_the_line() {
+	let program = parser::parse("Void main() {\n\tif true {\n\t\treturn\n\t}\n\treturn\n\tdebug(1.0)\n}\n").unwrap();
+
+	let main = program.get_function("main".to_owned()).unwrap();
+	assert_eq!(main.statements.len(), 3);
+	assert!(matches!(main.statements[1], Statement::Return(_, None)));
+	assert!(matches!(main.statements[2], Statement::Expression(_)));
+}
+
+#[test]
+pub fn misplaced_doc_comments() {
+	let (program, errors) = parser::parse_recovering(
+		r"
+Float scale(
+	/// the factor
+	Float x
+) {
+	return x * 2.0
+}
+
+uniform
+/// brightness
+Float brightness
+
+/// Entry point.
+Float main() {
+	return 1.0
+}
+",
+	);
+
+	assert_eq!(errors.len(), 2);
+	assert!(matches!(errors[0], parser::ParsingError::MisplacedDocComment(_)));
+	assert_eq!(errors[0].span().unwrap().from.line, 3);
+	assert!(matches!(errors[1], parser::ParsingError::MisplacedDocComment(_)));
+	assert_eq!(errors[1].span().unwrap().from.line, 10);
+
+	assert!(program.get_function("main".to_owned()).is_some());
+	assert!(program.get_function("scale".to_owned()).is_none());
+}
+
+#[test]
+pub fn doc_comments_are_attached() {
+	let program = parser::parse(
+		r"
+/// Overall brightness.
+/// Ranges from 0 to 1.
+uniform Float brightness
+
+/// A light source.
+struct Light {
+	Vec3 color
+}
+
+/// Entry point.
+Float main() {
+	/// not attached to anything
+	return brightness
+}
+",
+	)
+	.unwrap();
+
+	assert_eq!(
+		program.in_parameters[0].doc.as_deref(),
+		Some("Overall brightness.\nRanges from 0 to 1.")
+	);
+	assert_eq!(program.struct_declarations[0].doc.as_deref(), Some("A light source."));
+	assert_eq!(program.functions[0].doc.as_deref(), Some("Entry point."));
+}
@@ -0,0 +1,114 @@
+use motokigo::{
+	ast::SourceId,
+	parser,
+	scanner::{Scanner, ScanningError, Token},
+};
+
+#[test]
+pub fn positions_are_exact() {
+	let source = "let größe = 1.0 // comment\n\tx <= größe";
+	let tokens = Scanner::new(source.chars()).scan_all().unwrap();
+
+	let spans = tokens
+		.iter()
+		.map(|t| (t.from.line, t.from.column, t.to.column, t.slice(source).unwrap()))
+		.collect::<Vec<_>>();
+
+	assert_eq!(
+		spans,
+		vec![
+			(1, 1, 4, "let"),
+			(1, 5, 10, "größe"),
+			(1, 11, 12, "="),
+			(1, 13, 16, "1.0"),
+			(2, 2, 3, "x"),
+			(2, 4, 6, "<="),
+			(2, 7, 12, "größe"),
+		]
+	);
+	assert_eq!(tokens[6].item, Token::Identifier("größe".to_owned()));
+	assert_eq!(tokens[6].byte_range(), 35..42);
+}
+
+#[test]
+pub fn positions_carry_source_id() {
+	let program = parser::parse_source("Float main() {\n\treturn 1.0\n}", SourceId(3)).unwrap();
+
+	let ident = &program.functions[0].ident;
+	assert_eq!(ident.source(), SourceId(3));
+	assert_eq!(ident.slice("Float main() {\n\treturn 1.0\n}"), Some("main"));
+}
+
+#[test]
+pub fn block_comments_nest() {
+	let source = "a /* outer /* inner */ still outer */ b";
+	let tokens = Scanner::new(source.chars()).scan_all().unwrap();
+
+	assert_eq!(tokens.len(), 2);
+	assert_eq!(tokens[1].slice(source), Some("b"));
+}
+
+#[test]
+pub fn unterminated_block_comment() {
+	let source = "a /* /* */";
+	let (tokens, errors) = Scanner::new(source.chars()).scan_all_recovering();
+
+	assert_eq!(tokens.len(), 1);
+	assert!(matches!(errors[..], [ScanningError::UnterminatedBlockComment(_)]));
+}
+
+#[test]
+pub fn line_comment_at_end_of_file() {
+	let tokens = Scanner::new("a // no newline".chars()).scan_all().unwrap();
+
+	assert_eq!(tokens.len(), 1);
+}
+
+#[test]
+pub fn doc_comments() {
+	let source = "/// Brightness\n////not a doc comment\nx";
+	let tokens = Scanner::new(source.chars()).scan_all().unwrap();
+
+	assert_eq!(tokens[0].item, Token::DocComment("Brightness".to_owned()));
+	assert_eq!(tokens[1].item, Token::Identifier("x".to_owned()));
+}
+
+#[test]
+pub fn numeric_literals() {
+	let source = "1e-3 .5 2.5E2 0xff 0b1010 1_000_000 1u 0xFFFF_FFFFu 1.0f 3f 7";
+	let tokens = Scanner::new(source.chars())
+		.scan_all()
+		.unwrap()
+		.into_iter()
+		.map(|t| t.item)
+		.collect::<Vec<_>>();
+
+	assert_eq!(
+		tokens,
+		vec![
+			Token::FloatLiteral(1e-3),
+			Token::FloatLiteral(0.5),
+			Token::FloatLiteral(250.0),
+			Token::IntegerLiteral(255),
+			Token::IntegerLiteral(10),
+			Token::IntegerLiteral(1_000_000),
+			Token::UnsignedIntegerLiteral(1),
+			Token::UnsignedIntegerLiteral(0xFFFF_FFFF),
+			Token::FloatLiteral(1.0),
+			Token::FloatLiteral(3.0),
+			Token::IntegerLiteral(7),
+		]
+	);
+}
+
+#[test]
+pub fn invalid_numeric_literals() {
+	for source in &["1e", "0x", "0b102", "1.0u", "0x1_0000_0000", "12abc"] {
+		let (_, errors) = Scanner::new(source.chars()).scan_all_recovering();
+		assert!(
+			matches!(errors[..], [ScanningError::InvalidLiteral(_)]),
+			"{} should be invalid",
+			source
+		);
+	}
+}